@@ -0,0 +1,46 @@
+#[macro_use]
+extern crate criterion;
+extern crate lalrpop_lambda;
+
+use std::convert::TryFrom;
+use criterion::Criterion;
+use lalrpop_lambda::{Application, Expression, Prelude, Strategy};
+
+const INPUTS: [u64; 4] = [50, 100, 200, 400];
+
+// Church arithmetic on hundreds, `3n`.
+fn arithmetic(n: u64) -> Expression {
+    let f = Prelude::new()
+        .parse("λn.add n (mul n (succ (succ zero)))")
+        .unwrap();
+    Expression::App(Application(Box::new(f), Box::new(n.into())))
+}
+
+fn normalize_benchmark(c: &mut Criterion) {
+    for &(name, strategy) in &[
+        ("normal order arithmetic", Strategy::Normal(false)),
+        ("applicative order arithmetic", Strategy::Applicative(false)),
+        ("call-by-need arithmetic", Strategy::CallByNeed),
+    ] {
+        c.bench_function_over_inputs(
+            name,
+            move |b, &n| {
+                let e = arithmetic(n);
+                b.iter(|| u64::try_from(e.normalize(&strategy)).unwrap())
+            },
+            INPUTS,
+        );
+    }
+
+    c.bench_function_over_inputs(
+        "nbe arithmetic",
+        |b, &n| {
+            let e = arithmetic(n);
+            b.iter(|| u64::try_from(e.nbe()).unwrap())
+        },
+        INPUTS,
+    );
+}
+
+criterion_group!(benches, normalize_benchmark);
+criterion_main!(benches);
@@ -2,8 +2,9 @@
 extern crate criterion;
 extern crate lalrpop_lambda;
 
+use std::convert::TryFrom;
 use criterion::Criterion;
-use lalrpop_lambda::Expression;
+use lalrpop_lambda::{Application, Binary, Expression, Prelude, Strategy};
 
 fn compare_benchmark(c: &mut Criterion) {
     c.bench_function_over_inputs("native addition", |b, &n| {
@@ -15,9 +16,35 @@ fn compare_benchmark(c: &mut Criterion) {
     c.bench_function_over_inputs("λ-expression addition", |b, &n| {
         b.iter(|| {
             let e = Expression::from(*n);
-            u64::from(e.clone() + e)
+            u64::try_from(e.clone() + e).unwrap()
         })
     }, &[0,1,2,4,8,16,32]);
+
+    c.bench_function_over_inputs("binary λ-expression addition", |b, &n| {
+        b.iter(|| {
+            let e = Expression::from(Binary(*n));
+            let add = Binary::add()(e.clone())(e);
+            Binary::try_from(add).unwrap()
+        })
+    }, &[0u64,1,32,1024,1 << 20,1 << 40]);
+
+    // Doubling a power of two uses its argument twice.
+    let double = Prelude::new().parse("λn.(λm.add m m) (pow (succ (succ zero)) n)").unwrap();
+    let apply = move |n: u64| {
+        Expression::App(Application(Box::new(double.clone()), Box::new(n.into())))
+    };
+    for &(name, strategy) in &[
+        ("normal order λ-expression doubling", Strategy::Normal(false)),
+        ("call-by-need λ-expression doubling", Strategy::CallByNeed),
+    ] {
+        let apply = apply.clone();
+        c.bench_function_over_inputs(name, move |b, &n| {
+            b.iter(|| {
+                let e = apply(*n).normalize(&strategy);
+                u64::try_from(e).unwrap()
+            })
+        }, &[0u64,1,2,3,4,5]);
+    }
 }
 
 criterion_group!(benches, compare_benchmark);
@@ -56,6 +56,10 @@ fn main() {
     println!();
     parse!(r"\x.(x x) (\x.(x x))");
     parse!(r"(\x.(x x)) (\x.(x x))");
-    // XXX: Blows the stack in our strategy.
-    parse!(r"(\x.(x x)) (\x.(x x))", Expression::normalize);
+    // Normalizing Ω never terminates, so give up after a number of steps.
+    let limit = |e: &Expression, s: &Strategy| match e.normalize_with_limit(s, 1000) {
+        Ok(e) => e,
+        Err(exhausted) => exhausted.expression,
+    };
+    parse!(r"(\x.(x x)) (\x.(x x))", limit);
 }
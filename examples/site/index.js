@@ -52,9 +52,9 @@ import("./node_modules/lalrpop-lambda/lalrpop_lambda.js").then(wasm => {
         { label: 'cbv ->',   func: 'call_by_value' },
         { label: 'norm ->',  func: 'normal' },
         { label: 'cbn ->',   func: 'call_by_name' },
-        // TODO: Hybrid by-func and applicative.
+        { label: 'hybrid app ->',  func: 'hybrid_applicative' },
         { label: 'spine ->', func: 'head_spine' },
-        // TODO: Hybrid head spine and normal.
+        { label: 'hybrid norm ->', func: 'hybrid_normal' },
         { label: "numeral =", func: 'toNumber' },
         { label: "bool =",    func: 'toBool' },
       ];
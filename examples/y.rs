@@ -2,13 +2,18 @@
 extern crate lalrpop_lambda;
 
 use lalrpop_lambda::parse::ExpressionParser;
+use lalrpop_lambda::{Prelude, Variable};
 
 fn main() {
     let parser = ExpressionParser::new();
+    let prelude = Prelude::new();
 
     // Make the Y combinator.
     println!("ω = {}", parser.parse(r"λx.(x x)").unwrap());
     println!("Ω = {}", parser.parse(r"(λx.(x x)) (λx.(x x))").unwrap());
-    println!("W = {}", parser.parse(r"λf.λx. f x x").unwrap());
-    println!("Y = {}", parser.parse(r"λf.(λx.f (x x)) (λx.f (x x))").unwrap());
+
+    // Or take it, and its friends, from the prelude.
+    for name in &["W", "Y", "Z", "Theta"] {
+        println!("{} = {}", name, prelude[&Variable(name.to_string(), None)]);
+    }
 }
@@ -0,0 +1,148 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{Abstraction, Application, Expression, Variable};
+
+impl Expression {
+    /// Equality up to α-conversion
+    ///
+    /// Two expressions are α-equivalent when they only differ in the names of
+    /// their bound variables.
+    ///
+    /// ```
+    /// let parser = lalrpop_lambda::parse::ExpressionParser::new();
+    ///
+    /// let x = parser.parse("λx.λy.x").unwrap();
+    /// let a = parser.parse("λa.λb.a").unwrap();
+    /// let b = parser.parse("λa.λb.b").unwrap();
+    ///
+    /// assert!(x.alpha_eq(&a));
+    /// assert!(!x.alpha_eq(&b));
+    /// ```
+    pub fn alpha_eq(&self, other: &Expression) -> bool {
+        alpha_eq(self, other, &mut vec![], &mut vec![])
+    }
+}
+
+/// An [`Expression`] compared and hashed up to α-conversion
+///
+/// ```
+/// use lalrpop_lambda::Alpha;
+///
+/// let parser = lalrpop_lambda::parse::ExpressionParser::new();
+///
+/// assert_eq!(Alpha(parser.parse("λx.x").unwrap()),
+///            Alpha(parser.parse("λy.y").unwrap()));
+/// assert_ne!(Alpha(parser.parse("λx.y").unwrap()),
+///            Alpha(parser.parse("λy.y").unwrap()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Alpha(pub Expression);
+
+impl PartialEq for Alpha {
+    fn eq(&self, other: &Alpha) -> bool {
+        self.0.alpha_eq(&other.0)
+    }
+}
+
+impl Eq for Alpha {}
+
+impl Hash for Alpha {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash(&self.0, &mut vec![], state)
+    }
+}
+
+// Bound variables are compared by the position of their binders, free
+// variables by name.
+fn alpha_eq<'a>(
+    e1: &'a Expression,
+    e2: &'a Expression,
+    bound1: &mut Vec<&'a Variable>,
+    bound2: &mut Vec<&'a Variable>,
+) -> bool {
+    match (e1, e2) {
+        (Expression::Var(x1), Expression::Var(x2)) => {
+            match (binder(x1, bound1), binder(x2, bound2)) {
+                (Some(i1), Some(i2)) => i1 == i2,
+                (None, None) => x1 == x2,
+                _ => false,
+            }
+        }
+        (Expression::Abs(Abstraction(id1, body1)), Expression::Abs(Abstraction(id2, body2))) => {
+            if id1.1 != id2.1 {
+                return false;
+            }
+            bound1.push(id1);
+            bound2.push(id2);
+            let eq = alpha_eq(body1, body2, bound1, bound2);
+            bound1.pop();
+            bound2.pop();
+            eq
+        }
+        (Expression::App(Application(e11, e12)), Expression::App(Application(e21, e22))) => {
+            alpha_eq(e11, e21, bound1, bound2) && alpha_eq(e12, e22, bound1, bound2)
+        }
+        _ => false,
+    }
+}
+
+fn hash<'a, H: Hasher>(e: &'a Expression, bound: &mut Vec<&'a Variable>, state: &mut H) {
+    match e {
+        Expression::Var(x) => match binder(x, bound) {
+            Some(i) => (0u8, i).hash(state),
+            None => (1u8, x).hash(state),
+        },
+        Expression::Abs(Abstraction(id, body)) => {
+            (2u8, &id.1).hash(state);
+            bound.push(id);
+            hash(body, bound, state);
+            bound.pop();
+        }
+        Expression::App(Application(e1, e2)) => {
+            3u8.hash(state);
+            hash(e1, bound, state);
+            hash(e2, bound, state);
+        }
+    }
+}
+
+// The number of binders between a variable and the abstraction binding it.
+fn binder(x: &Variable, bound: &[&Variable]) -> Option<usize> {
+    bound.iter().rev().position(|b| b.0 == x.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::ExpressionParser;
+    use std::collections::HashSet;
+
+    #[test]
+    fn alpha_eq() {
+        let parser = ExpressionParser::new();
+        let eq = |a, b| parser.parse(a).unwrap().alpha_eq(&parser.parse(b).unwrap());
+
+        assert!(eq(r"x", r"x"));
+        assert!(!eq(r"x", r"y"));
+        assert!(eq(r"λx.x", r"λy.y"));
+        assert!(eq(r"λx.λy.x y", r"λy.λx.y x"));
+        assert!(eq(r"λx.λx.x", r"λy.λx.x"));
+        assert!(!eq(r"λx.λx.x", r"λx.λy.x"));
+        assert!(!eq(r"λx.y", r"λy.y"));
+        assert!(!eq(r"λx.x", r"x"));
+        assert!(!eq(r"λx:t.x", r"λx:u.x"));
+        assert!(eq(r"(λx.x) (λy.y)", r"(λa.a) (λa.a)"));
+    }
+
+    #[test]
+    fn hash() {
+        let parser = ExpressionParser::new();
+        let mut set = HashSet::new();
+        set.insert(Alpha(parser.parse(r"λx.λy.x").unwrap()));
+        set.insert(Alpha(parser.parse(r"λa.λb.a").unwrap()));
+        set.insert(Alpha(parser.parse(r"λa.λb.b").unwrap()));
+        set.insert(Alpha(parser.parse(r"λa.λb.c").unwrap()));
+
+        assert_eq!(3, set.len());
+    }
+}
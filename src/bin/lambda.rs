@@ -0,0 +1,340 @@
+//! An interactive λ-calculus REPL
+//!
+//! Enter a λ-expression to normalize it, or a command, see `:help`.
+extern crate lalrpop_lambda;
+extern crate rustyline;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use lalrpop_lambda::parse::{ExpressionParser, VariableParser};
+use lalrpop_lambda::{
+    Context, Diagnostic, Exhausted, Expression, Limit, Limits, Prelude, Strategy, Variable,
+};
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+
+const HELP: &str = "\
+<term>                 normalize a term with the current strategy
+:strategy [<name> [η]] show or set the strategy, one of:
+                         bn (call by name), no (normal), bv (call by value),
+                         ao (applicative), he (head spine),
+                         hn (hybrid normal), ha (hybrid applicative),
+                         nd (call by need)
+:step <term>           reduce a term by a single step
+:trace <term>          show every step of reducing a term
+:let <name> = <term>   define a name for use in later terms
+:env                   show every definition
+:prelude               define the standard combinators and operations, like
+                         S, K, I, Y, succ, pair and cons
+:fv <term>             show the free variables of a term
+:type <term>           infer the type of a term, annotations like \\x:a.x
+                         are optional
+:num <term>            decode a term as a church numeral
+:bool <term>           decode a term as a church boolean
+:help                  show this message
+:quit                  exit";
+
+/// Bounds on reducing any one term, so divergent or growing terms don't hang
+/// the REPL or exhaust its memory.
+const LIMITS: Limits = Limits {
+    steps: Some(10_000),
+    size: Some(1 << 16),
+    depth: Some(1 << 10),
+};
+
+struct Repl {
+    parser: ExpressionParser,
+    env: HashMap<Variable, Expression>,
+    strategy: Strategy,
+}
+
+impl Repl {
+    fn new() -> Self {
+        Repl {
+            parser: ExpressionParser::new(),
+            env: HashMap::new(),
+            strategy: Strategy::Normal(false),
+        }
+    }
+
+    /// Run a single line of input, returning the output to show
+    fn eval(&mut self, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        let (command, rest) = if line.starts_with(':') {
+            let end = line.find(char::is_whitespace).unwrap_or(line.len());
+            (&line[..end], line[end..].trim())
+        } else {
+            ("", line)
+        };
+
+        match command {
+            "" if rest.is_empty() => Ok(String::new()),
+            "" => {
+                let e = self.parse(rest)?;
+                match e.normalize_with_limits(&self.strategy, &LIMITS) {
+                    Ok(e) => Ok(e.to_string()),
+                    Err(exhausted) => Err(format!("{}: {}", exhausted, exhausted.expression)),
+                }
+            }
+            ":strategy" => {
+                if !rest.is_empty() {
+                    self.strategy = strategy(rest)?;
+                }
+                Ok(format!("{:?}", self.strategy))
+            }
+            ":step" => match self.parse(rest)?.apply(&self.strategy) {
+                Some(e) => Ok(e.to_string()),
+                None => Err("already in normal form".into()),
+            },
+            ":trace" => {
+                let mut e = self.parse(rest)?;
+                let mut trace = vec![e.to_string()];
+                for (steps, next) in e.reductions(&self.strategy).enumerate() {
+                    if let Some(limit) = exceeded(steps, &next) {
+                        let exhausted = Exhausted {
+                            limit,
+                            expression: e,
+                        };
+                        trace.push(exhausted.to_string());
+                        return Err(trace.join("\n"));
+                    }
+                    trace.push(format!("→ {}", next));
+                    e = next;
+                }
+                Ok(trace.join("\n"))
+            }
+            ":let" => {
+                let eq = rest.find('=').ok_or("expected <name> = <term>")?;
+                let name = rest[..eq].trim();
+                let id = VariableParser::new()
+                    .parse(name)
+                    .map_err(|e| Diagnostic::new(&e, name).render(name))?;
+                let e = self.parse(rest[eq + 1..].trim())?;
+                let output = format!("{} := {}", id, e);
+                self.env.insert(id, e);
+                Ok(output)
+            }
+            ":env" => {
+                let mut env: Vec<_> = self
+                    .env
+                    .iter()
+                    .map(|(id, e)| format!("{} := {}", id, e))
+                    .collect();
+                env.sort();
+                Ok(env.join("\n"))
+            }
+            ":prelude" => {
+                let prelude = Prelude::new();
+                let output = format!("defined {} names", prelude.len());
+                self.env.extend(HashMap::from(prelude));
+                Ok(output)
+            }
+            ":fv" => {
+                let mut free: Vec<_> = self
+                    .parse(rest)?
+                    .free_variables()
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect();
+                free.sort();
+                Ok(format!("{{{}}}", free.join(", ")))
+            }
+            ":type" => self
+                .parse(rest)?
+                .infer(&Context::new())
+                .map(|ty| ty.to_string())
+                .map_err(|e| e.to_string()),
+            ":num" => u64::try_from(self.parse(rest)?)
+                .map(|n| n.to_string())
+                .map_err(|e| e.to_string()),
+            ":bool" => bool::try_from(self.parse(rest)?)
+                .map(|p| p.to_string())
+                .map_err(|e| e.to_string()),
+            ":help" => Ok(HELP.into()),
+            _ => Err(format!("unknown command {}, see :help", command)),
+        }
+    }
+
+    /// Parse a term, and resolve the names defined with `:let` in it, which may
+    /// refer to each other recursively
+    fn parse(&self, source: &str) -> Result<Expression, String> {
+        self.parser
+            .parse(source)
+            .map(|e| e.resolve_recursive(&self.env))
+            .map_err(|e| Diagnostic::new(&e, source).render(source))
+    }
+}
+
+/// The limit in `LIMITS` broken by taking a step to `e`, after `steps` steps.
+fn exceeded(steps: usize, e: &Expression) -> Option<Limit> {
+    match LIMITS {
+        Limits {
+            steps: Some(limit), ..
+        } if steps >= limit => Some(Limit::Steps(limit)),
+        Limits {
+            size: Some(limit), ..
+        } if e.size() > limit => Some(Limit::Size(limit)),
+        Limits {
+            depth: Some(limit), ..
+        } if e.depth() > limit => Some(Limit::Depth(limit)),
+        _ => None,
+    }
+}
+
+fn strategy(s: &str) -> Result<Strategy, String> {
+    let mut words = s.split_whitespace();
+    let name = words.next().unwrap_or("");
+    let η = match words.next() {
+        None => false,
+        Some("η") | Some("eta") => true,
+        Some(word) => return Err(format!("expected η, found {}", word)),
+    };
+
+    match (name, η) {
+        ("bn", false) => Ok(Strategy::CallByName),
+        ("no", η) => Ok(Strategy::Normal(η)),
+        ("bv", false) => Ok(Strategy::CallByValue),
+        ("ao", η) => Ok(Strategy::Applicative(η)),
+        ("he", η) => Ok(Strategy::HeadSpine(η)),
+        ("hn", false) => Ok(Strategy::HybridNormal),
+        ("ha", false) => Ok(Strategy::HybridApplicative),
+        ("nd", false) => Ok(Strategy::CallByNeed),
+        (name, true) => Err(format!("{} doesn't support η-reduction", name)),
+        (name, false) => Err(format!("unknown strategy {}, see :help", name)),
+    }
+}
+
+fn main() {
+    let mut repl = Repl::new();
+    let mut editor = Editor::<()>::new();
+
+    loop {
+        match editor.readline("λ> ") {
+            Ok(line) => {
+                editor.add_history_entry(line.as_str());
+                if line.trim() == ":quit" {
+                    break;
+                }
+                match repl.eval(&line) {
+                    Ok(output) if output.is_empty() => {}
+                    Ok(output) => println!("{}", output),
+                    Err(error) => eprintln!("{}", error),
+                }
+            }
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => {
+                eprintln!("{}", error);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        let mut repl = Repl::new();
+        assert_eq!(Ok("a".into()), repl.eval(r"(\x.x) a"));
+        assert_eq!(Ok("".into()), repl.eval("  "));
+        assert!(repl.eval(r"(\x.x x) (\x.x x)").is_err());
+        assert!(repl.eval(r"(\x.").unwrap_err().contains("^"));
+    }
+
+    #[test]
+    fn strategy() {
+        let mut repl = Repl::new();
+        assert_eq!(Ok("Normal(false)".into()), repl.eval(":strategy"));
+        assert_eq!(Ok("Applicative(true)".into()), repl.eval(":strategy ao η"));
+        assert_eq!(Ok("CallByName".into()), repl.eval(":strategy bn"));
+        assert_eq!(Ok(r"λx.(λy.y) x".into()), repl.eval(r"\x.(\y.y) x"));
+        assert!(repl.eval(":strategy bn η").is_err());
+        assert!(repl.eval(":strategy xx").is_err());
+        assert_eq!(Ok("CallByName".into()), repl.eval(":strategy"));
+        assert_eq!(Ok("CallByNeed".into()), repl.eval(":strategy nd"));
+        assert_eq!(Ok(r"λx.x".into()), repl.eval(r"\x.(\y.y) x"));
+    }
+
+    #[test]
+    fn step_and_trace() {
+        let mut repl = Repl::new();
+        assert_eq!(Ok("(λy.a y) b".into()), repl.eval(r":step (\x.\y.x y) a b"));
+        assert!(repl.eval(":step a").is_err());
+        assert_eq!(
+            Ok("(λx y.x y) a b\n→ (λy.a y) b\n→ a b".into()),
+            repl.eval(r":trace (\x.\y.x y) a b")
+        );
+        let trace = repl.eval(r":trace (\x.x x x) (\x.x x x)").unwrap_err();
+        assert!(trace.ends_with("exceeded a term depth of 1024"));
+        let trace = repl.eval(r":trace (\x.x x) (\x.x x)").unwrap_err();
+        assert!(trace.ends_with("exceeded 10000 reduction steps"));
+    }
+
+    #[test]
+    fn let_() {
+        let mut repl = Repl::new();
+        assert_eq!(Ok("id := λx.x".into()), repl.eval(r":let id = \x.x"));
+        assert_eq!(
+            Ok("two := λf x.f (f x)".into()),
+            repl.eval(r":let two = \f x.f (f x)")
+        );
+        assert_eq!(Ok("2".into()), repl.eval(":num id two"));
+        assert_eq!(Ok("λx.x".into()), repl.eval("id id"));
+        assert!(repl.eval(":let id").is_err());
+        assert!(repl.eval(r":let \x = x").is_err());
+        assert_eq!(
+            Ok("id := λx.x\ntwo := λf x.f (f x)".into()),
+            repl.eval(":env")
+        );
+    }
+
+    #[test]
+    fn prelude() {
+        let mut repl = Repl::new();
+        assert_eq!(Ok("S K K a".into()), repl.eval("S K K a"));
+        assert_eq!(Ok("defined 36 names".into()), repl.eval(":prelude"));
+        assert_eq!(Ok("a".into()), repl.eval("S K K a"));
+        assert_eq!(
+            Ok("3".into()),
+            repl.eval(":num succ (add (succ zero) (succ zero))")
+        );
+        assert_eq!(
+            Ok("I := λx.x".into()),
+            repl.eval(":env").map(|env| {
+                env.lines()
+                    .find(|l| l.starts_with("I "))
+                    .unwrap()
+                    .to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn recursion() {
+        let mut repl = Repl::new();
+        repl.eval(":prelude").unwrap();
+        repl.eval(r":let even = \n.if (is_zero n) true (odd (pred n))")
+            .unwrap();
+        repl.eval(r":let odd = \n.if (is_zero n) false (even (pred n))")
+            .unwrap();
+        assert_eq!(Ok("true".into()), repl.eval(":bool odd (succ zero)"));
+        assert_eq!(Ok("false".into()), repl.eval(":bool even (succ zero)"));
+    }
+
+    #[test]
+    fn decode() {
+        let mut repl = Repl::new();
+        assert_eq!(Ok("{x, z}".into()), repl.eval(r":fv \y.x y z"));
+        assert_eq!(Ok("0".into()), repl.eval(r":num \f x.x"));
+        assert_eq!(Ok("true".into()), repl.eval(r":bool \a b.a"));
+        assert_eq!(Ok("a → b → a".into()), repl.eval(r":type \x:a y:b.x"));
+        assert_eq!(Ok("∀a. a → a".into()), repl.eval(r":type \x.x"));
+        assert!(repl.eval(r":type \x.x x").is_err());
+        assert!(repl.eval(r":bool \a.a").is_err());
+        assert!(repl.eval(":nope").is_err());
+    }
+}
@@ -0,0 +1,434 @@
+use std::fmt;
+
+use crate::{Abstraction, Application, Expression, Variable};
+
+/// A term of combinatory logic
+///
+/// Terms are built by [`Expression::combinators`], and reduced by weak
+/// reduction, which never looks under a combinator missing its arguments.
+///
+/// ```
+/// use lalrpop_lambda::{Basis, Combinator, Expression};
+///
+/// let parser = lalrpop_lambda::parse::ExpressionParser::new();
+///
+/// let k = parser.parse("λx.λy.x").unwrap().combinators(Basis::Ski);
+/// assert_eq!(Combinator::K, k);
+/// let flip = parser.parse("λf.λa.λb.f b a").unwrap();
+/// assert_eq!("S (S (K S) (S (K K) S)) (K K)",
+///            flip.combinators(Basis::Ski).to_string());
+/// assert_eq!(Combinator::C, flip.combinators(Basis::Turner));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Combinator {
+    /// `S x y z → x z (y z)`
+    S,
+    /// `K x y → x`
+    K,
+    /// `I x → x`
+    I,
+    /// `B x y z → x (y z)`
+    B,
+    /// `C x y z → x z y`
+    C,
+    /// `W x y → x y y`
+    W,
+    /// A free variable
+    Var(Variable),
+    App(Box<Combinator>, Box<Combinator>),
+}
+
+/// The combinators a λ-expression is translated to, and how
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Basis {
+    /// S, K and I by the textbook translation, which grows quickly
+    Naive,
+    /// S, K and I, with the standard optimizations of `K` for a body without
+    /// the variable, and η-reduction
+    Ski,
+    /// Turner's B and C as well, for a variable on only one side of an
+    /// application
+    Turner,
+    /// B, C, K and W alone, with `S` as `B (B W) (B B C)` and `I` as `W K`
+    Bckw,
+}
+
+impl Expression {
+    /// Translate into combinators, by bracket abstraction
+    ///
+    /// Each abstraction `λx.M` becomes `[x]M`, a term without `x` which
+    /// behaves like `M` once applied to `x`. Free variables are kept as
+    /// [`Combinator::Var`].
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::{Basis, Expression, Strategy};
+    ///
+    /// let three = Expression::from(3u64).combinators(Basis::Ski);
+    /// let back = Expression::from(&three).normalize(&Strategy::Normal(false));
+    /// assert_eq!(Ok(3), u64::try_from(back));
+    /// ```
+    pub fn combinators(&self, basis: Basis) -> Combinator {
+        match self {
+            Expression::Var(id) => Combinator::Var(id.clone()),
+            Expression::Abs(Abstraction(id, body)) => {
+                body.combinators(basis).abstraction(id, basis)
+            }
+            Expression::App(Application(e1, e2)) => {
+                Combinator::app(e1.combinators(basis), e2.combinators(basis))
+            }
+        }
+    }
+}
+
+impl Combinator {
+    /// Weak reduction small-step semantics
+    ///
+    /// Contracts the leftmost outermost redex, returning `None` once there are
+    /// none left.
+    ///
+    /// ```
+    /// use lalrpop_lambda::{Basis, Combinator, Variable};
+    ///
+    /// let parser = lalrpop_lambda::parse::ExpressionParser::new();
+    ///
+    /// let e = parser.parse("(λx.λy.y x) a").unwrap().combinators(Basis::Turner);
+    /// assert_eq!("C I a", e.to_string());
+    /// assert_eq!(None, e.step());
+    /// let e = Combinator::app(e, Combinator::Var(Variable("b".into(), None)));
+    /// assert_eq!("I b a", e.step().unwrap().to_string());
+    /// ```
+    pub fn step(&self) -> Option<Self> {
+        let mut head = self;
+        let mut arguments = vec![];
+        while let Combinator::App(f, a) = head {
+            arguments.push(&**a);
+            head = f;
+        }
+        arguments.reverse();
+
+        let arity = match head {
+            Combinator::I => 1,
+            Combinator::K | Combinator::W => 2,
+            Combinator::S | Combinator::B | Combinator::C => 3,
+            _ => usize::MAX,
+        };
+        if arguments.len() >= arity {
+            let (x, rest) = arguments.split_at(arity);
+            let app = |a: &Combinator, b: &Combinator| Combinator::app(a.clone(), b.clone());
+            let contracted = match head {
+                Combinator::I => x[0].clone(),
+                Combinator::K => x[0].clone(),
+                Combinator::W => Combinator::app(app(x[0], x[1]), x[1].clone()),
+                Combinator::S => Combinator::app(app(x[0], x[2]), app(x[1], x[2])),
+                Combinator::B => Combinator::app(x[0].clone(), app(x[1], x[2])),
+                Combinator::C => Combinator::app(app(x[0], x[2]), x[1].clone()),
+                _ => unreachable!(),
+            };
+            return Some(rest.iter().fold(contracted, |f, &a| app(&f, a)));
+        }
+
+        // The head is stuck, so reduce the arguments from left to right.
+        for (i, a) in arguments.iter().enumerate() {
+            if let Some(a) = a.step() {
+                let mut arguments: Vec<_> = arguments.iter().map(|&a| a.clone()).collect();
+                arguments[i] = a;
+                return Some(arguments.into_iter().fold(head.clone(), Combinator::app));
+            }
+        }
+        None
+    }
+
+    /// Big-step weak reduction, which diverges when there is no normal form
+    ///
+    /// ```
+    /// use lalrpop_lambda::{Basis, Combinator};
+    ///
+    /// let parser = lalrpop_lambda::parse::ExpressionParser::new();
+    ///
+    /// let e = parser.parse("(λf.λx.f (f x)) g y").unwrap().combinators(Basis::Ski);
+    /// assert_eq!("g (g y)", e.normalize().to_string());
+    /// ```
+    pub fn normalize(&self) -> Self {
+        let mut term = self.clone();
+        while let Some(next) = term.step() {
+            term = next;
+        }
+        term
+    }
+
+    /// Big-step weak reduction, taking at most `fuel` steps
+    ///
+    /// The last term reached is returned as an error when the fuel runs out.
+    ///
+    /// ```
+    /// use lalrpop_lambda::Combinator::*;
+    ///
+    /// let ω = lalrpop_lambda::Combinator::app(W, I);
+    /// let Ω = lalrpop_lambda::Combinator::app(ω.clone(), ω);
+    /// assert!(Ω.normalize_with_limit(100).is_err());
+    /// ```
+    pub fn normalize_with_limit(&self, fuel: usize) -> Result<Self, Self> {
+        let mut term = self.clone();
+        for _ in 0..fuel {
+            match term.step() {
+                Some(next) => term = next,
+                None => return Ok(term),
+            }
+        }
+        match term.step() {
+            Some(_) => Err(term),
+            None => Ok(term),
+        }
+    }
+
+    /// The number of combinators, variables and applications in the term
+    pub fn size(&self) -> usize {
+        match self {
+            Combinator::App(f, a) => 1 + f.size() + a.size(),
+            _ => 1,
+        }
+    }
+
+    pub fn app(f: Combinator, a: Combinator) -> Self {
+        Combinator::App(Box::new(f), Box::new(a))
+    }
+
+    // Is `x` free in this term?
+    fn contains(&self, x: &Variable) -> bool {
+        match self {
+            Combinator::Var(id) => id.0 == x.0,
+            Combinator::App(f, a) => f.contains(x) || a.contains(x),
+            _ => false,
+        }
+    }
+
+    // [x]M, the bracket abstraction of `x` from this term.
+    fn abstraction(&self, x: &Variable, basis: Basis) -> Self {
+        use self::Combinator::*;
+
+        let s = || match basis {
+            Basis::Bckw => Combinator::app(
+                Combinator::app(B, Combinator::app(B, W)),
+                Combinator::app(Combinator::app(B, B), C),
+            ),
+            _ => S,
+        };
+        let i = || match basis {
+            Basis::Bckw => Combinator::app(W, K),
+            _ => I,
+        };
+
+        if basis == Basis::Naive {
+            return match self {
+                Var(id) if id.0 == x.0 => I,
+                App(f, a) => Combinator::app(
+                    Combinator::app(S, f.abstraction(x, basis)),
+                    a.abstraction(x, basis),
+                ),
+                _ => Combinator::app(K, self.clone()),
+            };
+        }
+
+        match self {
+            _ if !self.contains(x) => Combinator::app(K, self.clone()),
+            Var(_) => i(),
+            App(f, a) => match (f.contains(x), &**a) {
+                (false, Var(_)) => (**f).clone(),
+                (false, _) if basis != Basis::Ski => {
+                    Combinator::app(Combinator::app(B, (**f).clone()), a.abstraction(x, basis))
+                }
+                (true, _) if !a.contains(x) && basis != Basis::Ski => {
+                    Combinator::app(Combinator::app(C, f.abstraction(x, basis)), (**a).clone())
+                }
+                _ => Combinator::app(
+                    Combinator::app(s(), f.abstraction(x, basis)),
+                    a.abstraction(x, basis),
+                ),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// The λ-expression of each combinator
+///
+/// ```
+/// use lalrpop_lambda::{Combinator, Expression};
+///
+/// assert_eq!("λx y z.x z (y z)", Expression::from(&Combinator::S).to_string());
+/// ```
+impl From<&Combinator> for Expression {
+    fn from(term: &Combinator) -> Self {
+        match term {
+            Combinator::S => λ! {x y z.γ!(γ!(x,z),γ!(y,z))},
+            Combinator::K => λ! {x y.x},
+            Combinator::I => λ! {x.x},
+            Combinator::B => λ! {x y z.γ!(x,γ!(y,z))},
+            Combinator::C => λ! {x y z.γ!(γ!(x,z),y)},
+            Combinator::W => λ! {x y.γ!(γ!(x,y),y)},
+            Combinator::Var(id) => Expression::Var(id.clone()),
+            Combinator::App(f, a) => γ!({ Expression::from(&**f) }, { Expression::from(&**a) }),
+        }
+    }
+}
+
+impl From<Combinator> for Expression {
+    fn from(term: Combinator) -> Self {
+        Expression::from(&term)
+    }
+}
+
+impl fmt::Display for Combinator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Combinator::S => write!(f, "S"),
+            Combinator::K => write!(f, "K"),
+            Combinator::I => write!(f, "I"),
+            Combinator::B => write!(f, "B"),
+            Combinator::C => write!(f, "C"),
+            Combinator::W => write!(f, "W"),
+            Combinator::Var(id) => write!(f, "{}", id),
+            Combinator::App(e1, e2) => match **e2 {
+                Combinator::App(_, _) => write!(f, "{} ({})", e1, e2),
+                _ => write!(f, "{} {}", e1, e2),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Combinator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::ExpressionParser;
+    use crate::{Prelude, Strategy};
+
+    const BASES: [Basis; 4] = [Basis::Naive, Basis::Ski, Basis::Turner, Basis::Bckw];
+
+    #[test]
+    fn bracket_abstraction() {
+        let parser = ExpressionParser::new();
+        let compile =
+            |source: &str, basis| parser.parse(source).unwrap().combinators(basis).to_string();
+
+        assert_eq!("I", compile("λx.x", Basis::Naive));
+        assert_eq!("S (K K) I", compile("λx.λy.x", Basis::Naive));
+        assert_eq!("K", compile("λx.λy.x", Basis::Ski));
+        assert_eq!("K (K a)", compile("λx.λy.a", Basis::Ski));
+        assert_eq!("f", compile("λx.f x", Basis::Ski));
+        assert_eq!("S I I", compile("λx.x x", Basis::Ski));
+        assert_eq!("B f g", compile("λx.f (g x)", Basis::Turner));
+        assert_eq!("C f a", compile("λx.f x a", Basis::Turner));
+        assert_eq!("W K", compile("λx.x", Basis::Bckw));
+        assert_eq!(
+            "B (B W) (B B C) (W K) (W K)",
+            compile("λx.x x", Basis::Bckw)
+        );
+        // Inner binders shadow outer ones.
+        assert_eq!("K I", compile("λx.λx.x", Basis::Ski));
+    }
+
+    #[test]
+    fn reduce() {
+        let (f, x) = (Combinator::Var(variable!(f)), Combinator::Var(variable!(x)));
+        let prelude = Prelude::new();
+        let five = prelude
+            .parse("add (succ (succ zero)) (succ (succ (succ zero)))")
+            .unwrap();
+
+        for &basis in &BASES {
+            let e = Combinator::app(
+                Combinator::app(five.combinators(basis), f.clone()),
+                x.clone(),
+            );
+            assert_eq!(
+                "f (f (f (f (f x))))",
+                e.normalize().to_string(),
+                "{:?}",
+                basis
+            );
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn reduce_Ω() {
+        let Ω = ExpressionParser::new().parse("(λx.x x) (λx.x x)").unwrap();
+        for &basis in &BASES {
+            let Ω = Ω.combinators(basis);
+            assert_eq!(Err(Ω.clone()), Ω.normalize_with_limit(0));
+            assert!(Ω.normalize_with_limit(1000).is_err());
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let prelude = Prelude::new();
+        for (id, e) in prelude.iter() {
+            // The fixed point combinators have no normal form.
+            if ["Y", "Z", "Theta"].contains(&id.0.as_str()) {
+                continue;
+            }
+            let normal = βη(e);
+            for &basis in &BASES {
+                // The naive translation is too slow to normalize for larger
+                // terms.
+                if basis == Basis::Naive && e.size() > 16 {
+                    continue;
+                }
+                let back = βη(&Expression::from(e.combinators(basis)));
+                assert!(
+                    back.alpha_eq(&normal),
+                    "{} in {:?}: {} ≠ {}",
+                    id,
+                    basis,
+                    back,
+                    normal
+                );
+            }
+        }
+    }
+
+    // The βη-normal form, by η-reducing the β-normal form from the inside
+    // out, which can't make any new β-redexes.
+    fn βη(e: &Expression) -> Expression {
+        fn η(e: &Expression) -> Expression {
+            match e {
+                Expression::Var(_) => e.clone(),
+                Expression::Abs(Abstraction(id, body)) => match η(body) {
+                    Expression::App(Application(e1, box Expression::Var(x)))
+                        if x.0 == id.0 && !e1.free_variables().iter().any(|v| v.0 == id.0) =>
+                    {
+                        *e1
+                    }
+                    body => Expression::Abs(Abstraction(id.clone(), Box::new(body))),
+                },
+                Expression::App(Application(e1, e2)) => {
+                    Expression::App(Application(Box::new(η(e1)), Box::new(η(e2))))
+                }
+            }
+        }
+        η(&e.normalize(&Strategy::Normal(false)))
+    }
+
+    #[test]
+    fn sizes() {
+        let prelude = Prelude::new();
+        let pred = &prelude[&variable!(pred)];
+        let sizes: Vec<_> = BASES
+            .iter()
+            .map(|&basis| pred.combinators(basis).size())
+            .collect();
+
+        // Each optimization makes smaller terms, but BCKW pays for its lack
+        // of S.
+        assert!(sizes[0] > sizes[1] && sizes[1] > sizes[2], "{:?}", sizes);
+        assert!(sizes[3] > sizes[2], "{:?}", sizes);
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{Abstraction, Application, Expression, Type, Variable};
+
+/// A nameless λ-expression using De Bruijn indices
+///
+/// Each bound variable is the number of abstractions between it and its
+/// binder, so α-equivalent expressions are structurally equal, and
+/// substitution never needs to rename anything.
+///
+/// ```
+/// use lalrpop_lambda::{DeBruijn, Expression};
+/// use lalrpop_lambda::parse::ExpressionParser;
+///
+/// let parser = ExpressionParser::new();
+/// let k = parser.parse("λx.λy.x").unwrap();
+///
+/// assert_eq!(DeBruijn::from(&k), DeBruijn::from(&parser.parse("λa.λb.a").unwrap()));
+/// assert_eq!("(λ.(λ.1))", DeBruijn::from(&k).to_string());
+/// assert!(k.alpha_eq(&Expression::from(&DeBruijn::from(&k))));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum DeBruijn {
+    /// A bound variable, by its index
+    Var(usize),
+    /// A free variable, by its name
+    Free(Variable),
+    /// An abstraction, and the type annotation of its bound variable
+    Abs(Option<Type>, Box<DeBruijn>),
+    App(Box<DeBruijn>, Box<DeBruijn>),
+}
+
+impl DeBruijn {
+    /// ↑ᵈ꜀, add `d` to every variable index at least `c`
+    ///
+    /// ```
+    /// use lalrpop_lambda::DeBruijn;
+    ///
+    /// let e = DeBruijn::Abs(None, Box::new(DeBruijn::App(
+    ///     Box::new(DeBruijn::Var(0)),
+    ///     Box::new(DeBruijn::Var(1)))));
+    /// let shifted = DeBruijn::Abs(None, Box::new(DeBruijn::App(
+    ///     Box::new(DeBruijn::Var(0)),
+    ///     Box::new(DeBruijn::Var(3)))));
+    ///
+    /// assert_eq!(shifted, e.shift(2, 0));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// When an index would be shifted below zero.
+    pub fn shift(&self, d: isize, c: usize) -> Self {
+        match self {
+            DeBruijn::Var(k) if *k >= c => DeBruijn::Var(
+                k.checked_add_signed(d)
+                    .expect("variable index shifted out of range"),
+            ),
+            DeBruijn::Var(_) | DeBruijn::Free(_) => self.clone(),
+            DeBruijn::Abs(ty, body) => DeBruijn::Abs(ty.clone(), Box::new(body.shift(d, c + 1))),
+            DeBruijn::App(e1, e2) => {
+                DeBruijn::App(Box::new(e1.shift(d, c)), Box::new(e2.shift(d, c)))
+            }
+        }
+    }
+
+    /// self[j := s]
+    pub fn substitute(&self, j: usize, s: &Self) -> Self {
+        match self {
+            DeBruijn::Var(k) if *k == j => s.clone(),
+            DeBruijn::Var(_) | DeBruijn::Free(_) => self.clone(),
+            DeBruijn::Abs(ty, body) => {
+                DeBruijn::Abs(ty.clone(), Box::new(body.substitute(j + 1, &s.shift(1, 0))))
+            }
+            DeBruijn::App(e1, e2) => {
+                DeBruijn::App(Box::new(e1.substitute(j, s)), Box::new(e2.substitute(j, s)))
+            }
+        }
+    }
+
+    /// Contract the redex (λ.self) v
+    pub fn beta(&self, v: &Self) -> Self {
+        self.substitute(0, &v.shift(1, 0)).shift(-1, 0)
+    }
+
+    /// Normal order reduction to β-normal form
+    ///
+    /// ```
+    /// use lalrpop_lambda::{DeBruijn, Expression};
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let expression = parser.parse("(λx.λy.x y) (λz.y z)").unwrap();
+    /// let normal = parser.parse("λa.y a").unwrap();
+    ///
+    /// assert_eq!(DeBruijn::from(&normal), DeBruijn::from(&expression).normalize());
+    /// ```
+    pub fn normalize(&self) -> Self {
+        match self {
+            DeBruijn::Var(_) | DeBruijn::Free(_) => self.clone(),
+            DeBruijn::Abs(ty, body) => DeBruijn::Abs(ty.clone(), Box::new(body.normalize())),
+            DeBruijn::App(e1, e2) => match e1.whnf() {
+                DeBruijn::Abs(_, body) => body.beta(e2).normalize(),
+                e => DeBruijn::App(Box::new(e.normalize()), Box::new(e2.normalize())),
+            },
+        }
+    }
+
+    // Call by name reduction to weak head normal form.
+    fn whnf(&self) -> Self {
+        match self {
+            DeBruijn::App(e1, e2) => match e1.whnf() {
+                DeBruijn::Abs(_, body) => body.beta(e2).whnf(),
+                e => DeBruijn::App(Box::new(e), e2.clone()),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    // The number of abstractions the loose indices need around the term.
+    fn loose(&self) -> usize {
+        match self {
+            DeBruijn::Var(k) => k + 1,
+            DeBruijn::Free(_) => 0,
+            DeBruijn::Abs(_, body) => body.loose().saturating_sub(1),
+            DeBruijn::App(e1, e2) => e1.loose().max(e2.loose()),
+        }
+    }
+
+    fn free_variables(&self) -> HashSet<&Variable> {
+        match self {
+            DeBruijn::Free(v) => set! { v },
+            DeBruijn::Var(_) => set! {},
+            DeBruijn::Abs(_, body) => body.free_variables(),
+            DeBruijn::App(e1, e2) => e1
+                .free_variables()
+                .union(&e2.free_variables())
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl From<&Expression> for DeBruijn {
+    fn from(e: &Expression) -> Self {
+        fn convert<'a>(e: &'a Expression, bound: &mut Vec<&'a str>) -> DeBruijn {
+            match e {
+                Expression::Var(v) => match bound.iter().rev().position(|b| *b == v.0) {
+                    Some(k) => DeBruijn::Var(k),
+                    None => DeBruijn::Free(v.clone()),
+                },
+                Expression::Abs(Abstraction(id, body)) => {
+                    bound.push(&id.0);
+                    let body = convert(body, bound);
+                    bound.pop();
+                    DeBruijn::Abs(id.1.clone(), Box::new(body))
+                }
+                Expression::App(Application(e1, e2)) => {
+                    DeBruijn::App(Box::new(convert(e1, bound)), Box::new(convert(e2, bound)))
+                }
+            }
+        }
+
+        convert(e, &mut vec![])
+    }
+}
+
+/// Convert back to a named expression
+///
+/// Bound variables are named `x`, `y`, `z`, ... avoiding both the free
+/// variables and the names bound by enclosing abstractions. Loose indices,
+/// without an abstraction binding them, become free variables named as if
+/// they were bound by abstractions around the whole term.
+///
+/// ```
+/// use lalrpop_lambda::{DeBruijn, Expression};
+///
+/// let loose = DeBruijn::Abs(None, Box::new(DeBruijn::Var(1)));
+/// assert_eq!("λy.x", Expression::from(&loose).to_string());
+/// ```
+impl From<&DeBruijn> for Expression {
+    fn from(e: &DeBruijn) -> Self {
+        fn name(bound: &[String], free: &HashSet<&str>) -> String {
+            (0..)
+                .flat_map(|i| {
+                    NAMES.iter().map(move |n| {
+                        if i == 0 {
+                            n.to_string()
+                        } else {
+                            format!("{}{}", n, i)
+                        }
+                    })
+                })
+                .find(|n| !free.contains(n.as_str()) && !bound.contains(n))
+                .unwrap()
+        }
+
+        fn convert(e: &DeBruijn, bound: &mut Vec<String>, free: &HashSet<&str>) -> Expression {
+            match e {
+                DeBruijn::Var(k) => {
+                    Expression::Var(Variable(bound[bound.len() - 1 - k].clone(), None))
+                }
+                DeBruijn::Free(v) => Expression::Var(v.clone()),
+                DeBruijn::Abs(ty, body) => {
+                    let name = name(bound, free);
+                    bound.push(name.clone());
+                    let body = convert(body, bound, free);
+                    bound.pop();
+                    Expression::Abs(Abstraction(Variable(name, ty.clone()), Box::new(body)))
+                }
+                DeBruijn::App(e1, e2) => Expression::App(Application(
+                    Box::new(convert(e1, bound, free)),
+                    Box::new(convert(e2, bound, free)),
+                )),
+            }
+        }
+
+        let free = e
+            .free_variables()
+            .into_iter()
+            .map(|v| v.0.as_str())
+            .collect();
+        let mut bound = vec![];
+        for _ in 0..e.loose() {
+            let name = name(&bound, &free);
+            bound.push(name);
+        }
+        convert(e, &mut bound, &free)
+    }
+}
+
+const NAMES: [&str; 6] = ["x", "y", "z", "u", "v", "w"];
+
+impl fmt::Debug for DeBruijn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeBruijn::Var(k) => write!(f, "{}", k),
+            DeBruijn::Free(v) => write!(f, "{:?}", v),
+            DeBruijn::Abs(Some(ty), body) => write!(f, "(λ:{}.{:?})", ty, body),
+            DeBruijn::Abs(None, body) => write!(f, "(λ.{:?})", body),
+            DeBruijn::App(e1, e2) => write!(f, "({:?} {:?})", e1, e2),
+        }
+    }
+}
+
+impl fmt::Display for DeBruijn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::ExpressionParser;
+    use crate::Strategy;
+    use pretty_assertions::assert_eq;
+
+    fn abs(body: DeBruijn) -> DeBruijn {
+        DeBruijn::Abs(None, Box::new(body))
+    }
+
+    fn app(e1: DeBruijn, e2: DeBruijn) -> DeBruijn {
+        DeBruijn::App(Box::new(e1), Box::new(e2))
+    }
+
+    #[test]
+    fn from_expression() {
+        let parser = ExpressionParser::new();
+        let convert = |e| DeBruijn::from(&parser.parse(e).unwrap());
+
+        assert_eq!(DeBruijn::Free(variable!(x)), convert(r"x"));
+        assert_eq!(abs(DeBruijn::Var(0)), convert(r"λx.x"));
+        assert_eq!(abs(abs(DeBruijn::Var(1))), convert(r"λx.λy.x"));
+        assert_eq!(abs(abs(DeBruijn::Var(0))), convert(r"λx.λx.x"));
+        assert_eq!(
+            abs(app(DeBruijn::Var(0), DeBruijn::Free(variable!(y)))),
+            convert(r"λx.x y")
+        );
+        assert_eq!(
+            DeBruijn::Abs(Some(Type::Base("t".into())), Box::new(DeBruijn::Var(0))),
+            convert(r"λx:t.x")
+        );
+        assert_eq!(convert(r"λf.λx.f (f x)"), convert(r"λa.λb.a (a b)"));
+    }
+
+    #[test]
+    fn to_expression() {
+        let parser = ExpressionParser::new();
+
+        for e in &[
+            r"x",
+            r"λx.x",
+            r"λy.λx.x y",
+            r"λx.λx.x",
+            r"λx.λx1.x z x1",
+            r"(λx.x y) (λy.x y)",
+            r"λx:t.λf:u.f x",
+        ] {
+            let expression = parser.parse(e).unwrap();
+            let round_trip = Expression::from(&DeBruijn::from(&expression));
+            assert!(
+                expression.alpha_eq(&round_trip),
+                "{} != {}",
+                expression,
+                round_trip
+            );
+        }
+
+        // Names are readable, and never capture free variables.
+        assert_eq!(
+            parser.parse(r"λy.λz.y x").unwrap(),
+            Expression::from(&abs(abs(app(
+                DeBruijn::Var(1),
+                DeBruijn::Free(variable!(x))
+            ))))
+        );
+    }
+
+    #[test]
+    fn loose_to_expression() {
+        let parser = ExpressionParser::new();
+
+        assert_eq!(var!(x), Expression::from(&DeBruijn::Var(0)));
+        // Loose indices are named apart from free variables, outermost first.
+        assert_eq!(
+            parser.parse(r"λu.y (z x)").unwrap(),
+            Expression::from(&abs(app(
+                DeBruijn::Var(2),
+                app(DeBruijn::Var(1), DeBruijn::Free(variable!(x)))
+            )))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "variable index shifted out of range")]
+    fn shift_below_zero() {
+        DeBruijn::Var(0).shift(-1, 0);
+    }
+
+    #[test]
+    fn substitute() {
+        // (λ.1 0)[0 := 5] = λ.6 0
+        assert_eq!(
+            abs(app(DeBruijn::Var(6), DeBruijn::Var(0))),
+            abs(app(DeBruijn::Var(1), DeBruijn::Var(0))).substitute(0, &DeBruijn::Var(5))
+        );
+        // (λ.λ.0 1) applied to λ.0
+        assert_eq!(
+            abs(app(DeBruijn::Var(0), abs(DeBruijn::Var(0)))),
+            abs(app(DeBruijn::Var(0), DeBruijn::Var(1))).beta(&abs(DeBruijn::Var(0)))
+        );
+    }
+
+    #[test]
+    fn normalize() {
+        let parser = ExpressionParser::new();
+
+        for e in &[
+            r"(λx.λy.x) y",
+            r"(λx.λy.λz.x z (y z)) (λx.λy.x) (λx.λy.x)",
+            r"(λn.λf.λx.f (n f x)) ((λn.λf.λx.f (n f x)) (λf.λx.x))",
+            r"(λm.λn.λf.λx.m f (n f x)) (λf.λx.f x) (λf.λx.f (f x))",
+            r"(λx.y) ((λx.x x) (λx.x x))",
+        ] {
+            let expression = parser.parse(e).unwrap();
+            assert_eq!(
+                DeBruijn::from(&expression.normalize(&Strategy::Normal(false))),
+                DeBruijn::from(&expression).normalize(),
+            );
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::error::Error;
+use std::fmt;
+
+use lalrpop_util::ParseError;
+
+use crate::Span;
+
+/// A parse error, located in the source it came from
+///
+/// ```
+/// use lalrpop_lambda::Diagnostic;
+/// use lalrpop_lambda::parse::ExpressionParser;
+///
+/// let source = r"(\x.";
+/// let error = ExpressionParser::new().parse(source).unwrap_err();
+/// let diagnostic = Diagnostic::new(&error, source);
+///
+/// assert_eq!(4, diagnostic.span.start);
+/// assert_eq!(vec![r#"")""#.to_string()], diagnostic.expected);
+/// assert_eq!(r#"error: unexpected end of input, expected ")"
+///  --> 1:5
+///   |
+/// 1 | (\x.
+///   |     ^
+/// "#, diagnostic.render(source));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+    /// The tokens which would have been accepted at `span`
+    pub expected: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Locate `error`, which came from parsing `source`
+    pub fn new<T, E>(error: &ParseError<usize, T, E>, source: &str) -> Self
+    where
+        T: fmt::Display,
+        E: fmt::Display,
+    {
+        let (message, span, expected) = match error {
+            ParseError::InvalidToken { location } => (
+                "invalid token".into(),
+                Span {
+                    start: *location,
+                    end: *location
+                        + source
+                            .get(*location..)
+                            .and_then(|rest| rest.chars().next())
+                            .map_or(1, char::len_utf8),
+                },
+                vec![],
+            ),
+            ParseError::UnrecognizedEOF { location, expected } => (
+                "unexpected end of input".into(),
+                Span {
+                    start: *location,
+                    end: *location,
+                },
+                expected.clone(),
+            ),
+            ParseError::UnrecognizedToken {
+                token: (start, token, end),
+                expected,
+            } => (
+                format!("unexpected `{}`", token),
+                Span {
+                    start: *start,
+                    end: *end,
+                },
+                expected.clone(),
+            ),
+            ParseError::ExtraToken {
+                token: (start, token, end),
+            } => (
+                format!("extra token `{}`", token),
+                Span {
+                    start: *start,
+                    end: *end,
+                },
+                vec![],
+            ),
+            ParseError::User { error } => (format!("{}", error), Span { start: 0, end: 0 }, vec![]),
+        };
+
+        Diagnostic {
+            message,
+            span,
+            expected,
+        }
+    }
+
+    /// The message with expected token hints, followed by the line of
+    /// `source` containing the error, with the error underlined
+    pub fn render(&self, source: &str) -> String {
+        let start = floor_char_boundary(source, self.span.start);
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let line = &source[line_start..line_end];
+        let number = source[..line_start].matches('\n').count() + 1;
+        let column = source[line_start..start].chars().count() + 1;
+        let end = floor_char_boundary(source, self.span.end.min(line_end).max(start));
+        let width = source[start..end]
+            .chars()
+            .count()
+            .max(1);
+        let gutter = " ".repeat(number.to_string().len());
+
+        format!(
+            "error: {}\n{}--> {}:{}\n{} |\n{} | {}\n{} | {}{}\n",
+            self,
+            gutter,
+            number,
+            column,
+            gutter,
+            number,
+            line,
+            gutter,
+            " ".repeat(column - 1),
+            "^".repeat(width)
+        )
+    }
+}
+
+/// The largest char boundary of `source` no greater than `index`
+fn floor_char_boundary(source: &str, index: usize) -> usize {
+    let mut index = index.min(source.len());
+    while !source.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some((last, rest)) = self.expected.split_last() {
+            let names = |token: &String| {
+                // The only regex terminal is the one for variable names.
+                if token.starts_with("r#") {
+                    "a variable".to_string()
+                } else {
+                    token.clone()
+                }
+            };
+
+            if rest.is_empty() {
+                write!(f, ", expected {}", names(last))?;
+            } else {
+                let rest: Vec<_> = rest.iter().map(names).collect();
+                write!(
+                    f,
+                    ", expected one of {}, or {}",
+                    rest.join(", "),
+                    names(last)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for Diagnostic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::ExpressionParser;
+    use pretty_assertions::assert_eq;
+
+    fn diagnose(source: &str) -> Diagnostic {
+        Diagnostic::new(&ExpressionParser::new().parse(source).unwrap_err(), source)
+    }
+
+    #[test]
+    fn unrecognized_token() {
+        let diagnostic = diagnose(r"\x.)");
+        assert_eq!(Span { start: 3, end: 4 }, diagnostic.span);
+        assert_eq!("unexpected `)`", diagnostic.message);
+    }
+
+    #[test]
+    fn invalid_token() {
+        let diagnostic = diagnose(r"x *");
+        assert_eq!(Span { start: 2, end: 3 }, diagnostic.span);
+        assert_eq!("invalid token", diagnostic.message);
+        assert_eq!("invalid token", diagnostic.to_string());
+    }
+
+    #[test]
+    fn invalid_multibyte_token() {
+        let source = "x ∀ y";
+        let diagnostic = diagnose(source);
+        assert_eq!(Span { start: 2, end: 5 }, diagnostic.span);
+        assert_eq!(
+            "error: invalid token\n --> 1:3\n  |\n1 | x ∀ y\n  |   ^\n",
+            diagnostic.render(source)
+        );
+
+        // Spans which don't fall on char boundaries are clamped.
+        let diagnostic = Diagnostic {
+            span: Span { start: 3, end: 4 },
+            ..diagnostic
+        };
+        assert_eq!(
+            "error: invalid token\n --> 1:3\n  |\n1 | x ∀ y\n  |   ^\n",
+            diagnostic.render(source)
+        );
+    }
+
+    #[test]
+    fn render() {
+        let source = "x\n  (y z";
+        let diagnostic = diagnose(source);
+        assert_eq!(
+            "error: unexpected end of input, expected \")\"\n \
+             --> 2:7\n  |\n2 |   (y z\n  |       ^\n",
+            diagnostic.render(source)
+        );
+
+        let source = "λx.λy. x λ ) y";
+        let diagnostic = diagnose(source);
+        assert_eq!(Span { start: 11, end: 13 }, diagnostic.span);
+        assert_eq!(
+            "error: unexpected `λ`, expected one of \"(\", \")\", \"->\", \".\", \":\", \"=\", \
+             \"in\", \"→\", or a variable\n --> 1:10\n  |\n1 | λx.λy. x λ ) y\n  |          ^\n",
+            diagnostic.render(source)
+        );
+    }
+}
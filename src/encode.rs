@@ -1,8 +1,148 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Abstraction, Exhausted, Expression, Limit, Limits, Strategy, Variable};
+
 // Functions themselves
 mod function;
 
 // Church booleans
 mod boolean;
 
-// Church numerals
+// Church numerals, and bytes as them
 mod numerals;
+pub use self::numerals::Byte;
+
+// Binary numerals, for large numbers
+mod binary;
+pub use self::binary::Binary;
+
+// Church pairs, options, results and lists, of other encoded types
+mod pair;
+mod option;
+mod result;
+mod list;
+
+// Signed integers, as pairs of church numerals
+mod integers;
+pub use self::integers::Signed;
+
+// Characters as binary numerals, and strings as lists of them
+mod text;
+
+/// The work allowed when decoding a λ term, before giving up on it ever
+/// reaching a normal form
+///
+/// Decoded terms may come from untrusted input, so they're bounded in size
+/// and depth as well as steps, to bound both memory and the stack. Terms
+/// already in normal form aren't reduced, so they're decoded at any size.
+const LIMITS: Limits = Limits {
+    steps: Some(1 << 12),
+    size: Some(1 << 14),
+    depth: Some(1 << 10),
+};
+
+/// A λ term which doesn't encode a value of the requested Rust type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The normal form has the wrong number of leading abstractions
+    Arity { expected: usize, found: usize },
+    /// The normal form isn't of the shape `λf.λx.f (f … x)`
+    NotANumeral(Expression),
+    /// The normal form is neither `λa.λb.a` nor `λa.λb.b`
+    NotABoolean(Expression),
+    /// The normal form isn't of the shape `λp.p a b`
+    NotAPair(Expression),
+    /// The normal form is neither `λn.λs.n` nor `λn.λs.s a`
+    NotAnOption(Expression),
+    /// The normal form is neither `λo.λe.o a` nor `λo.λe.e a`
+    NotAResult(Expression),
+    /// The normal form isn't of the shape `λc.λn.c a (c b … n)`
+    NotAList(Expression),
+    /// The normal form isn't of the shape `λz.λe.λo.z`, `λz.λe.λo.e n` or
+    /// `λz.λe.λo.o n`
+    NotABinary(Expression),
+    /// The normal form encodes a number too large for the Rust type
+    OutOfRange(Expression),
+    /// No normal form was found within the step limit, this is the last term
+    /// reached
+    Diverged(Expression),
+    /// The term grew past the size or depth limit before reaching a normal
+    /// form
+    Exhausted(Exhausted),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Arity { expected, found } => {
+                write!(f, "expected {} abstractions, found {}", expected, found)
+            }
+            DecodeError::NotANumeral(e) => write!(f, "{} is not a church numeral", e),
+            DecodeError::NotABoolean(e) => write!(f, "{} is not a church boolean", e),
+            DecodeError::NotAPair(e) => write!(f, "{} is not a church pair", e),
+            DecodeError::NotAnOption(e) => write!(f, "{} is not a church option", e),
+            DecodeError::NotAResult(e) => write!(f, "{} is not a church result", e),
+            DecodeError::NotAList(e) => write!(f, "{} is not a church list", e),
+            DecodeError::NotABinary(e) => write!(f, "{} is not a binary numeral", e),
+            DecodeError::OutOfRange(e) => write!(f, "{} is out of range", e),
+            DecodeError::Diverged(e) => write!(f, "no normal form found, reached {}", e),
+            DecodeError::Exhausted(e) => write!(f, "{}, reached {}", e, e.expression),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Normalize a λ term before decoding it
+fn normalize(e: Expression) -> Result<Expression, DecodeError> {
+    let strategy = Strategy::Normal(false);
+    if e.apply(&strategy).is_none() {
+        return Ok(e);
+    }
+
+    e.normalize_with_limits(&strategy, &LIMITS)
+        .map_err(|exhausted| match exhausted.limit {
+            Limit::Steps(_) => DecodeError::Diverged(exhausted.expression),
+            Limit::Size(_) | Limit::Depth(_) => DecodeError::Exhausted(exhausted),
+        })
+}
+
+/// Split a normalized term into exactly `arity` leading abstractions' bound
+/// variables and the remaining body
+fn abstractions(
+    e: &Expression,
+    arity: usize,
+) -> Result<(Vec<&Variable>, &Expression), DecodeError> {
+    let mut ids = vec![];
+    let mut body = e;
+    while let Expression::Abs(Abstraction(id, inner)) = body {
+        ids.push(id);
+        body = inner;
+    }
+
+    if ids.len() == arity {
+        Ok((ids, body))
+    } else {
+        Err(DecodeError::Arity {
+            expected: arity,
+            found: ids.len(),
+        })
+    }
+}
+
+/// A variable named `name`, primed until it isn't free in any of `es`, so
+/// it can bind them without capture
+fn binder(name: &str, es: &[&Expression]) -> Variable {
+    let mut id = Variable(name.into(), None);
+    while es.iter().any(|e| e.free_variables().iter().any(|v| v.0 == id.0)) {
+        id.0.push('\'');
+    }
+    id
+}
+
+/// Whether `e` is free of the variables bound by an encoding, as the parts of
+/// a value must be
+fn closed(e: &Expression, ids: &[&Variable]) -> bool {
+    !e.free_variables().iter().any(|v| ids.iter().any(|id| id.0 == v.0))
+}
@@ -0,0 +1,323 @@
+use std::convert::TryFrom;
+use crate::{Expression, Application};
+use super::{abstractions, closed, normalize, DecodeError};
+
+/// A natural number, encoded in binary rather than as a church numeral
+///
+/// Each number is one of three constructors, given the number with its
+/// lowest bit removed:
+///
+/// - `0` is `λz.λe.λo.z`
+/// - `2n` is `λz.λe.λo.e n`, for `n > 0`
+/// - `2n + 1` is `λz.λe.λo.o n`
+///
+/// So a number's encoding, and the combinators on it, are logarithmic in size.
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use lalrpop_lambda::{Binary, Expression};
+///
+/// # fn main() {
+/// let zero = λ!{z.λ!{e.λ!{o.z}}};
+/// assert_eq!(zero, Expression::from(Binary(0)));
+/// assert_eq!(λ!{z.λ!{e.λ!{o.γ!(o,{&zero})}}}, Expression::from(Binary(1)));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Binary(pub u64);
+
+impl From<Binary> for Expression {
+    fn from(Binary(n): Binary) -> Self {
+        if n == 0 {
+            λ!{z.λ!{e.λ!{o.z}}}
+        } else if n % 2 == 0 {
+            λ!{z.λ!{e.λ!{o.γ!(e,{Expression::from(Binary(n / 2))})}}}
+        } else {
+            λ!{z.λ!{e.λ!{o.γ!(o,{Expression::from(Binary(n / 2))})}}}
+        }
+    }
+}
+
+/// Convert λ term back to native Rust type
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::{Binary, DecodeError, Expression};
+///
+/// # fn main() {
+/// assert_eq!(Ok(Binary(6)), Binary::try_from(Expression::from(Binary(6))));
+/// assert_eq!(Err(DecodeError::NotABinary(λ!{z.λ!{e.λ!{o.e}}})),
+///            Binary::try_from(λ!{z.λ!{e.λ!{o.e}}}));
+/// # }
+/// ```
+impl TryFrom<Expression> for Binary {
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<Binary, DecodeError> {
+        let e = normalize(e)?;
+        decode(&e).map(Binary)
+    }
+}
+
+/// Decode a normalized binary numeral, and the numerals nested in it
+fn decode(e: &Expression) -> Result<u64, DecodeError> {
+    let (ids, body) = abstractions(e, 3)?;
+    let (z, even, odd) = (ids[0], ids[1], ids[2]);
+
+    // Later variables shadow earlier ones of the same name.
+    let bit = match body {
+        Expression::Var(v) if v.0 == z.0 && z.0 != even.0 && z.0 != odd.0 => {
+            return Ok(0);
+        }
+        Expression::App(Application(box Expression::Var(v), box n))
+            if closed(n, &[z, even, odd]) => {
+            if v.0 == odd.0 {
+                Some((1, n))
+            } else if v.0 == even.0 {
+                Some((0, n))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+
+    match bit {
+        Some((bit, n)) => decode(n)?
+            .checked_mul(2)
+            .and_then(|n| n.checked_add(bit))
+            .ok_or_else(|| DecodeError::OutOfRange(e.clone())),
+        None => Err(DecodeError::NotABinary(e.clone())),
+    }
+}
+
+impl Binary {
+    /// The successor of a binary numeral
+    ///
+    /// Like the other binary combinators, this is recursive with the Y
+    /// combinator, so needs normal order evaluation.
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::{Binary, Expression};
+    ///
+    /// # fn main() {
+    /// let succ = Binary::succ();
+    /// assert_eq!(Ok(Binary(8)), Binary::try_from(γ!({succ},{Expression::from(Binary(7))})));
+    /// # }
+    /// ```
+    pub fn succ() -> Expression {
+        // 0 + 1 = 1, 2m + 1 = 2m + 1, (2m + 1) + 1 = 2(m + 1)
+        let succ = λ!{s.λ!{n.γ!(γ!(γ!(n,{odd(zero())}),
+                                    λ!{m.{odd(var!(m))}}),
+                                 λ!{m.{even(γ!(s,m))}})}};
+        γ!({y()},{succ})
+    }
+
+    /// The sum of two binary numerals
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::{Binary, Expression};
+    ///
+    /// # fn main() {
+    /// let (m, n) = (Expression::from(Binary(1000)), Expression::from(Binary(2000)));
+    /// assert_eq!(Ok(Binary(3000)), Binary::try_from(γ!(γ!({Binary::add()},{m}),{n})));
+    /// # }
+    /// ```
+    pub fn add() -> Expression {
+        // The sums of the halves, with the carry added to them when both
+        // lowest bits are one.
+        let even_case = λ!{m.γ!(γ!(γ!(y,{even(var!(m))}),
+                                    λ!{k.{even(γ!(γ!(a,m),k))}}),
+                                 λ!{k.{odd(γ!(γ!(a,m),k))}})};
+        let odd_case = λ!{m.γ!(γ!(γ!(y,{odd(var!(m))}),
+                                   λ!{k.{odd(γ!(γ!(a,m),k))}}),
+                                λ!{k.{even(γ!({Binary::succ()},γ!(γ!(a,m),k)))}})};
+        let add = λ!{a.λ!{x.λ!{y.γ!(γ!(γ!(x,y),{even_case}),{odd_case})}}};
+        γ!({y()},{add})
+    }
+
+    /// The product of two binary numerals
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::{Binary, Expression};
+    ///
+    /// # fn main() {
+    /// let (m, n) = (Expression::from(Binary(300)), Expression::from(Binary(21)));
+    /// assert_eq!(Ok(Binary(6300)), Binary::try_from(γ!(γ!({Binary::mul()},{m}),{n})));
+    /// # }
+    /// ```
+    pub fn mul() -> Expression {
+        // For `y > 0`, 2m × y = 2(m × y), 1 × y = y, and (2m + 1) × y =
+        // y + 2(m × y). Each product is only used once, since nothing is
+        // shared by normal order evaluation.
+        let twice = even(γ!(γ!(p,m),y));
+        let plus = γ!(γ!({Binary::add()},y),{twice.clone()});
+        let mul = λ!{p.λ!{x.λ!{y.γ!(γ!(γ!(x,{zero()}),
+                                         λ!{m.{twice}}),
+                                      λ!{m.γ!(γ!(γ!(m,y),λ!{k.{plus.clone()}}),λ!{k.{plus}})})}}};
+        let product = γ!(γ!(γ!({y()},{mul}),x),y);
+        λ!{x.λ!{y.γ!(γ!(γ!(y,{zero()}),λ!{h.{product.clone()}}),λ!{h.{product}})}}
+    }
+
+    /// The church boolean of whether a binary numeral is less than or equal to
+    /// another
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::{Binary, Expression};
+    ///
+    /// # fn main() {
+    /// let (m, n) = (Expression::from(Binary(1023)), Expression::from(Binary(1024)));
+    /// assert_eq!(Ok(true), bool::try_from(γ!(γ!({Binary::leq()},{&m}),{&n})));
+    /// assert_eq!(Ok(false), bool::try_from(γ!(γ!({Binary::leq()},{&n}),{&m})));
+    /// # }
+    /// ```
+    pub fn leq() -> Expression {
+        // Compare the halves, strictly when the lowest bit of `x` is greater
+        // than that of `y`. The flag `s` is whether this comparison is strict.
+        let (t, f) = (λ!{a.λ!{b.a}}, λ!{a.λ!{b.b}});
+        let zero_case = γ!(γ!(γ!(y,γ!(γ!(s,{f.clone()}),{t.clone()})),λ!{k.{t.clone()}}),λ!{k.{t.clone()}});
+        let even_case = λ!{m.γ!(γ!(γ!(y,{f.clone()}),
+                                    λ!{k.γ!(γ!(γ!(l,s),m),k)}),
+                                 λ!{k.γ!(γ!(γ!(l,{f.clone()}),m),k)})};
+        let odd_case = λ!{m.γ!(γ!(γ!(y,{f.clone()}),
+                                   λ!{k.γ!(γ!(γ!(l,{t.clone()}),m),k)}),
+                                λ!{k.γ!(γ!(γ!(l,s),m),k)})};
+        let le = λ!{l.λ!{s.λ!{x.λ!{y.γ!(γ!(γ!(x,{zero_case}),{even_case}),{odd_case})}}}};
+        γ!(γ!({y()},{le}),{f})
+    }
+
+    /// The church boolean of whether two binary numerals are equal
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::{Binary, Expression};
+    ///
+    /// # fn main() {
+    /// let (m, n) = (Expression::from(Binary(1000)), Expression::from(Binary(1000)));
+    /// assert_eq!(Ok(true), bool::try_from(γ!(γ!({Binary::eq()},{m}),{n})));
+    /// # }
+    /// ```
+    pub fn eq() -> Expression {
+        let (t, f) = (λ!{a.λ!{b.a}}, λ!{a.λ!{b.b}});
+        let zero_case = γ!(γ!(γ!(y,{t.clone()}),λ!{k.{f.clone()}}),λ!{k.{f.clone()}});
+        let even_case = λ!{m.γ!(γ!(γ!(y,{f.clone()}),λ!{k.γ!(γ!(q,m),k)}),λ!{k.{f.clone()}})};
+        let odd_case = λ!{m.γ!(γ!(γ!(y,{f.clone()}),λ!{k.{f.clone()}}),λ!{k.γ!(γ!(q,m),k)})};
+        let eq = λ!{q.λ!{x.λ!{y.γ!(γ!(γ!(x,{zero_case}),{even_case}),{odd_case})}}};
+        γ!({y()},{eq})
+    }
+}
+
+/// λz.λe.λo.z
+fn zero() -> Expression {
+    λ!{z.λ!{e.λ!{o.z}}}
+}
+
+/// λz.λe.λo.e n
+fn even(n: Expression) -> Expression {
+    λ!{z.λ!{e.λ!{o.γ!(e,{n})}}}
+}
+
+/// λz.λe.λo.o n
+fn odd(n: Expression) -> Expression {
+    λ!{z.λ!{e.λ!{o.γ!(o,{n})}}}
+}
+
+/// λf.(λx.f (x x)) (λx.f (x x))
+fn y() -> Expression {
+    let half = λ!{x.γ!(f,γ!(x,x))};
+    λ!{f.γ!({&half},{&half})}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use pretty_assertions::assert_eq;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for n in (0..20).chain(vec![1 << 20, u64::MAX]) {
+            assert_eq!(Ok(Binary(n)), Expression::from(Binary(n)).try_into());
+        }
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(Expression::from(Binary(1 << 20)).size(),
+                   Expression::from(Binary((1 << 20) + 1)).size());
+        assert!(Expression::from(Binary(1 << 20)).size() < 150);
+    }
+
+    #[test]
+    fn not_a_binary() {
+        assert_eq!(Err(DecodeError::Arity { expected: 3, found: 2 }),
+                   Binary::try_from(Expression::from(true)));
+        assert_eq!(Err(DecodeError::NotABinary(λ!{z.λ!{e.λ!{o.γ!(z,o)}}})),
+                   Binary::try_from(λ!{z.λ!{e.λ!{o.γ!(z,o)}}}));
+        let max = Expression::from(Binary(u64::MAX));
+        assert!(matches!(Binary::try_from(γ!({Binary::succ()},{max})),
+                         Err(DecodeError::OutOfRange(_))));
+    }
+
+    // Each combinator against native `u64` arithmetic.
+    fn table<F, T>(combinator: Expression, f: F)
+        where F: Fn(u64, u64) -> T,
+              T: TryFrom<Expression, Error = DecodeError> + PartialEq + std::fmt::Debug
+    {
+        for &m in &[0, 1, 2, 3, 6, 7, 100, 255] {
+            for &n in &[0, 1, 2, 5, 8, 100, 256] {
+                let e = γ!(γ!({&combinator},{Expression::from(Binary(m))}),
+                           {Expression::from(Binary(n))});
+                assert_eq!(Ok(f(m, n)), T::try_from(e), "{} and {}", m, n);
+            }
+        }
+    }
+
+    #[test]
+    fn succ() {
+        for n in 0..20 {
+            let e = γ!({Binary::succ()},{Expression::from(Binary(n))});
+            assert_eq!(Ok(Binary(n + 1)), e.try_into());
+        }
+    }
+
+    #[test]
+    fn add() {
+        table(Binary::add(), |m, n| Binary(m + n));
+    }
+
+    #[test]
+    fn mul() {
+        table(Binary::mul(), |m, n| Binary(m * n));
+    }
+
+    #[test]
+    fn compare() {
+        table(Binary::leq(), |m, n| m <= n);
+        table(Binary::eq(), |m, n| m == n);
+    }
+}
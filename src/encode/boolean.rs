@@ -1,6 +1,8 @@
+use std::convert::TryFrom;
 use std::ops::{Not, BitAnd, BitOr, BitXor};
-use crate::{Expression, Abstraction};
+use crate::Expression;
 use crate::normal::Strategy;
+use super::{abstractions, normalize, DecodeError};
 
 /// Church encoded booleans
 ///
@@ -27,24 +29,30 @@ impl From<bool> for Expression {
 /// # #![feature(box_syntax)]
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::DecodeError;
+///
 /// # fn main() {
-/// assert_eq!(true , bool::from(λ!{a.λ!{b.a}}));
-/// assert_eq!(false, bool::from(λ!{a.λ!{b.γ!(b,b)}}));
+/// assert_eq!(Ok(true), bool::try_from(λ!{a.λ!{b.a}}));
+/// assert_eq!(Ok(false), bool::try_from(λ!{a.λ!{b.b}}));
+/// assert_eq!(Err(DecodeError::NotABoolean(λ!{a.λ!{b.γ!(b,b)}})),
+///            bool::try_from(λ!{a.λ!{b.γ!(b,b)}}));
 /// # }
 /// ```
-impl From<Expression> for bool {
-    fn from(e: Expression) -> bool {
-        let s = Strategy::Applicative(true);
-        if let Expression::Abs(Abstraction(a, box e1)) = e.normalize(&s) {
-           if let Expression::Abs(Abstraction(_, box e2)) = e1 {
-               if let Expression::Var(p) = e2 {
-                   return p == a
-               }
-            }
+impl TryFrom<Expression> for bool {
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<bool, DecodeError> {
+        let e = normalize(e)?;
+        let (ids, body) = abstractions(&e, 2)?;
+        let (a, b) = (ids[0], ids[1]);
+
+        // When both are named the same, `a` is shadowed by `b`.
+        match body {
+            Expression::Var(p) if p.0 == b.0 => Ok(false),
+            Expression::Var(p) if p.0 == a.0 => Ok(true),
+            _ => Err(DecodeError::NotABoolean(e.clone())),
         }
-
-        // Dirty hack, this should be an Option!
-        false
     }
 }
 
@@ -52,11 +60,13 @@ impl From<Expression> for bool {
 /// # #![feature(box_syntax)]
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+///
 /// # fn main() {
 /// let t = λ!{a.λ!{b.a}};
 ///
-/// assert_eq!(false, bool::from(!t.clone()));
-/// assert_eq!(true, bool::from(!!t.clone()));
+/// assert_eq!(Ok(false), bool::try_from(!t.clone()));
+/// assert_eq!(Ok(true), bool::try_from(!!t.clone()));
 /// # }
 /// ```
 ///
@@ -81,14 +91,16 @@ impl Not for Expression {
 /// # #![feature(box_syntax)]
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+///
 /// # fn main() {
 /// let t = λ!{a.λ!{b.a}};
 /// let f = λ!{a.λ!{b.b}};
 ///
-/// assert_eq!(true,  bool::from(t.clone() | t.clone()));
-/// assert_eq!(true,  bool::from(t.clone() | f.clone()));
-/// assert_eq!(true,  bool::from(f.clone() | t.clone()));
-/// assert_eq!(false, bool::from(f.clone() | f.clone()));
+/// assert_eq!(Ok(true),  bool::try_from(t.clone() | t.clone()));
+/// assert_eq!(Ok(true),  bool::try_from(t.clone() | f.clone()));
+/// assert_eq!(Ok(true),  bool::try_from(f.clone() | t.clone()));
+/// assert_eq!(Ok(false), bool::try_from(f.clone() | f.clone()));
 /// # }
 /// ```
 impl BitOr for Expression {
@@ -104,14 +116,16 @@ impl BitOr for Expression {
 /// # #![feature(box_syntax)]
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+///
 /// # fn main() {
 /// let t = λ!{a.λ!{b.a}};
 /// let f = λ!{a.λ!{b.b}};
 ///
-/// assert_eq!(true,  bool::from(t.clone() & t.clone()));
-/// assert_eq!(false, bool::from(t.clone() & f.clone()));
-/// assert_eq!(false, bool::from(f.clone() & t.clone()));
-/// assert_eq!(false, bool::from(f.clone() & f.clone()));
+/// assert_eq!(Ok(true),  bool::try_from(t.clone() & t.clone()));
+/// assert_eq!(Ok(false), bool::try_from(t.clone() & f.clone()));
+/// assert_eq!(Ok(false), bool::try_from(f.clone() & t.clone()));
+/// assert_eq!(Ok(false), bool::try_from(f.clone() & f.clone()));
 /// # }
 /// ```
 impl BitAnd for Expression {
@@ -127,14 +141,16 @@ impl BitAnd for Expression {
 /// # #![feature(box_syntax)]
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+///
 /// # fn main() {
 /// let t = λ!{a.λ!{b.a}};
 /// let f = λ!{a.λ!{b.b}};
 ///
-/// assert_eq!(false, bool::from(t.clone() ^ t.clone()));
-/// assert_eq!(true, bool::from(t.clone() ^ f.clone()));
-/// assert_eq!(true, bool::from(f.clone() ^ t.clone()));
-/// assert_eq!(false, bool::from(f.clone() ^ f.clone()));
+/// assert_eq!(Ok(false), bool::try_from(t.clone() ^ t.clone()));
+/// assert_eq!(Ok(true), bool::try_from(t.clone() ^ f.clone()));
+/// assert_eq!(Ok(true), bool::try_from(f.clone() ^ t.clone()));
+/// assert_eq!(Ok(false), bool::try_from(f.clone() ^ f.clone()));
 /// # }
 /// ```
 impl BitXor for Expression {
@@ -152,22 +168,32 @@ impl BitXor for Expression {
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryInto;
     use pretty_assertions::assert_eq;
     use super::*;
 
     #[test]
     fn true_() {
-        assert_eq!(true, Expression::from(true).into());
+        assert_eq!(Ok(true), Expression::from(true).try_into());
     }
 
     #[test]
     fn false_() {
-        assert_eq!(false, Expression::from(false).into());
+        assert_eq!(Ok(false), Expression::from(false).try_into());
+    }
+
+    #[test]
+    fn not_a_boolean() {
+        assert_eq!(Ok(false), bool::try_from(λ!{a.λ!{a.a}}));
+        assert_eq!(Err(DecodeError::Arity { expected: 2, found: 1 }),
+                   bool::try_from(λ!{a.a}));
+        assert_eq!(Err(DecodeError::NotABoolean(λ!{a.λ!{b.c}})),
+                   bool::try_from(λ!{a.λ!{b.c}}));
     }
 
     #[test]
     fn not() {
-        assert_eq!(false, (!Expression::from(true)).into());
+        assert_eq!(Ok(false), (!Expression::from(true)).try_into());
     }
 
     #[test]
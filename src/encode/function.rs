@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use crate::{Expression, Abstraction};
 
 /// Function call support for an `Expression`.
@@ -6,16 +7,18 @@ use crate::{Expression, Abstraction};
 /// # #![feature(box_syntax)]
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+///
 /// # fn main() {
-/// assert_eq!(0u64, λ!{x.x}(0).into());
+/// assert_eq!(Ok(0), u64::try_from(λ!{x.x}(0)));
 /// assert_eq!(γ!(γ!(a,b),0), γ!(a,b)(0));
 /// # }
 /// ```
 impl<T> FnOnce<(T,)> for Expression
     where T: Into<Expression> +
-             From<Expression>
+             TryFrom<Expression>
 {
-    // TODO: Return Option<T> here too.
+    // TODO: Return Result<T, T::Error> here too.
     type Output = Expression;
 
     extern "rust-call" fn call_once(self, t: (T,)) -> Expression {
@@ -55,6 +58,7 @@ impl From<fn(u64) -> u64> for Expression {
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -65,7 +69,7 @@ mod tests {
 
     #[test]
     fn abs() {
-        assert_eq!(5u64, abs!{x.x}(5).into());
+        assert_eq!(Ok(5), u64::try_from(abs!{x.x}(5)));
     }
 
     #[test]
@@ -0,0 +1,83 @@
+use std::convert::TryFrom;
+use crate::Expression;
+use super::{normalize, DecodeError};
+
+/// A signed integer, encoded as a pair of church numerals
+///
+/// An integer is a pair of church numerals `(p, n)`, standing for `p - n`.
+/// Encoding always gives a pair where one of them is zero. Like [`Byte`],
+/// this is a wrapper so integer literals are still inferred to be `u64`.
+///
+/// [`Byte`]: crate::Byte
+///
+/// ```
+/// use lalrpop_lambda::{Expression, Signed};
+///
+/// assert_eq!(Expression::from((3u64, 0u64)), Expression::from(Signed(3)));
+/// assert_eq!(Expression::from((0u64, 3u64)), Expression::from(Signed(-3)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Signed(pub i64);
+
+impl From<Signed> for Expression {
+    fn from(Signed(n): Signed) -> Self {
+        if n < 0 {
+            Expression::from((0u64, n.unsigned_abs()))
+        } else {
+            Expression::from((n as u64, 0u64))
+        }
+    }
+}
+
+/// Convert λ term back to native Rust type
+///
+/// Any pair of church numerals `(p, n)` decodes to `p - n`, if it fits.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::{Expression, Signed};
+///
+/// assert_eq!(Ok(Signed(-2)), Signed::try_from(Expression::from(Signed(-2))));
+/// assert_eq!(Ok(Signed(1)), Signed::try_from(Expression::from((3u64, 2u64))));
+/// ```
+impl TryFrom<Expression> for Signed {
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<Signed, DecodeError> {
+        let e = normalize(e)?;
+        let (p, n) = <(u64, u64)>::try_from(e.clone())?;
+        i64::try_from(i128::from(p) - i128::from(n))
+            .map(Signed)
+            .map_err(|_| DecodeError::OutOfRange(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use pretty_assertions::assert_eq;
+    use crate::parse::ExpressionParser;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for n in -3..=3 {
+            assert_eq!(Ok(Signed(n)), Expression::from(Signed(n)).try_into());
+        }
+    }
+
+    #[test]
+    fn negate() {
+        let negate = ExpressionParser::new().parse(r"λi.λp.i (λa b.p b a)").unwrap();
+        assert_eq!(Ok(Signed(-2)), negate.clone()(Expression::from(Signed(2))).try_into());
+        assert_eq!(Ok(Signed(2)), negate(Expression::from(Signed(-2))).try_into());
+    }
+
+    #[test]
+    fn not_an_integer() {
+        assert_eq!(Err(DecodeError::Arity { expected: 1, found: 2 }),
+                   Signed::try_from(Expression::from(1)));
+        assert_eq!(Err(DecodeError::NotANumeral(Expression::from(true))),
+                   Signed::try_from(Expression::from((true, 1u64))));
+    }
+}
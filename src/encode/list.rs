@@ -0,0 +1,122 @@
+use std::convert::TryFrom;
+use crate::{Expression, Application};
+use super::{abstractions, binder, closed, normalize, DecodeError};
+
+/// Church encoded lists, as their right fold
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use lalrpop_lambda::Expression;
+///
+/// # fn main() {
+/// let (t, f) = (Expression::from(true), Expression::from(false));
+/// assert_eq!(λ!{c.λ!{n.n}}, Expression::from(Vec::<bool>::new()));
+/// assert_eq!(λ!{c.λ!{n.γ!(γ!(c,{t}),γ!(γ!(c,{f}),n))}},
+///            Expression::from(vec![true, false]));
+/// # }
+/// ```
+impl<T> From<Vec<T>> for Expression
+    where T: Into<Expression>
+{
+    fn from(list: Vec<T>) -> Self {
+        let list: Vec<Expression> = list.into_iter().map(Into::into).collect();
+        let elements: Vec<&Expression> = list.iter().collect();
+        let (c, n) = (binder("c", &elements), binder("n", &elements));
+
+        let body = list.iter().rev().fold(Expression::Var(n.clone()), |tail, a| {
+            γ!(γ!({Expression::Var(c.clone())},{a}),{tail})
+        });
+        Expression::build_abs(1, vec![c, n], Some(body))
+    }
+}
+
+/// Convert λ term back to native Rust type
+///
+/// The term's normal form must be exactly `λc.λn.c a (c b … n)`, where each
+/// element decodes to the type of the list.
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::DecodeError;
+///
+/// # fn main() {
+/// let t = λ!{a.λ!{b.a}};
+/// assert_eq!(Ok(vec![]), Vec::<bool>::try_from(λ!{c.λ!{n.n}}));
+/// assert_eq!(Ok(vec![true, true]),
+///            Vec::<bool>::try_from(λ!{c.λ!{n.γ!(γ!(c,{&t}),γ!(γ!(c,{&t}),n))}}));
+/// assert_eq!(Err(DecodeError::NotAList(λ!{c.λ!{n.c}})),
+///            Vec::<bool>::try_from(λ!{c.λ!{n.c}}));
+/// # }
+/// ```
+impl<T> TryFrom<Expression> for Vec<T>
+    where T: TryFrom<Expression, Error = DecodeError>
+{
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<Vec<T>, DecodeError> {
+        let e = normalize(e)?;
+        let (ids, mut body) = abstractions(&e, 2)?;
+        let (c, n) = (ids[0], ids[1]);
+
+        // When both are named the same, `c` is shadowed by `n`.
+        let mut list = vec![];
+        while let Expression::App(Application(
+            box Expression::App(Application(box Expression::Var(d), box a)),
+            box tail,
+        )) = body {
+            if d.0 != c.0 || c.0 == n.0 || !closed(a, &[c, n]) {
+                break;
+            }
+            list.push(T::try_from(a.clone())?);
+            body = tail;
+        }
+
+        match body {
+            Expression::Var(m) if m.0 == n.0 => Ok(list),
+            _ => Err(DecodeError::NotAList(e.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use pretty_assertions::assert_eq;
+    use crate::parse::ExpressionParser;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(Ok(Vec::<u64>::new()), Expression::from(Vec::<u64>::new()).try_into());
+        assert_eq!(Ok(vec![3u64, 1, 2]), Expression::from(vec![3u64, 1, 2]).try_into());
+        assert_eq!(Ok(vec![(1u64, Some(true)), (0, None)]),
+                   Expression::from(vec![(1u64, Some(true)), (0, None)]).try_into());
+    }
+
+    #[test]
+    fn map_and_sum() {
+        let parser = ExpressionParser::new();
+        let succ = r"(λa f x.f (a f x))";
+        let map = parser.parse(&format!(r"λl.λc n.l (λa.c ({} a)) n", succ)).unwrap();
+        let sum = parser.parse(&format!(r"λl.l (λa b.a {} b) (λf x.x)", succ)).unwrap();
+
+        let list = Expression::from(vec![1u64, 2, 3]);
+        assert_eq!(Ok(vec![2u64, 3, 4]), map(list.clone()).try_into());
+        assert_eq!(Ok(6), u64::try_from(sum(list)));
+    }
+
+    #[test]
+    fn not_a_list() {
+        // Shadowing the `c` leaves only `n`.
+        assert_eq!(Ok(vec![]), Vec::<bool>::try_from(λ!{x.λ!{x.x}}));
+        assert_eq!(Err(DecodeError::NotAList(λ!{x.λ!{x.γ!(γ!(x,x),x)}})),
+                   Vec::<bool>::try_from(λ!{x.λ!{x.γ!(γ!(x,x),x)}}));
+        assert_eq!(Err(DecodeError::NotAList(λ!{c.λ!{n.γ!(γ!(c,n),n)}})),
+                   Vec::<bool>::try_from(λ!{c.λ!{n.γ!(γ!(c,n),n)}}));
+    }
+}
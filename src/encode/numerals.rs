@@ -1,6 +1,8 @@
-use std::ops::{Add, Mul};
-use crate::{Expression, Abstraction, Application, Variable};
+use std::convert::TryFrom;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use crate::{Expression, Application, Limits};
 use crate::normal::Strategy;
+use super::{abstractions, normalize, DecodeError};
 
 /// Church encoded natural numbers
 ///
@@ -18,57 +20,113 @@ use crate::normal::Strategy;
 /// ```
 impl From<u64> for Expression {
     fn from(n: u64) -> Self {
-        let succ = λ!{n.λ!{f.λ!{x.γ!(f, γ!(γ!(n, f), x))}}};
-        let mut e = λ!{f.λ!{x.x}};
+        // The same as normalizing `succ` applied `n` times, built directly
+        // so large numerals (like characters) are cheap.
+        let mut body = var!(x);
         for _ in 0..n {
-            e = app!({&succ}, {&e}).normalize(&Strategy::Applicative(false));
+            body = γ!(f,{body});
         }
-        e
+        λ!{f.λ!{x.{body}}}
+    }
+}
+
+/// A byte, encoded as a church numeral
+///
+/// This is a wrapper, rather than an encoding of `u8` itself, so integer
+/// literals are still inferred to be `u64` by `Expression::from`.
+///
+/// ```
+/// use lalrpop_lambda::{Byte, Expression};
+///
+/// assert_eq!(Expression::from(3), Expression::from(Byte(3)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Byte(pub u8);
+
+impl From<Byte> for Expression {
+    fn from(Byte(n): Byte) -> Self {
+        Expression::from(u64::from(n))
     }
 }
 
 /// Convert λ term back to native Rust type
 ///
+/// The term's normal form must be exactly `λf.λx.f (f … x)`.
+///
 /// ```
 /// # #![feature(box_syntax)]
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::DecodeError;
+///
 /// # fn main() {
-/// assert_eq!(0, u64::from(λ!{f.λ!{x.x}}));
-/// assert_eq!(1, u64::from(λ!{f.λ!{x.γ!(f,x)}}));
-/// assert_eq!(3, u64::from(λ!{f.λ!{x.γ!(f,γ!(f,γ!(f,x)))}}));
+/// assert_eq!(Ok(0), u64::try_from(λ!{f.λ!{x.x}}));
+/// assert_eq!(Ok(1), u64::try_from(λ!{f.λ!{x.γ!(f,x)}}));
+/// assert_eq!(Ok(3), u64::try_from(λ!{f.λ!{x.γ!(f,γ!(f,γ!(f,x)))}}));
+///
+/// assert_eq!(Err(DecodeError::Arity { expected: 2, found: 1 }),
+///            u64::try_from(λ!{x.x}));
+/// assert_eq!(Err(DecodeError::NotANumeral(λ!{f.λ!{x.γ!(x,f)}})),
+///            u64::try_from(λ!{f.λ!{x.γ!(x,f)}}));
 /// # }
 /// ```
-impl From<Expression> for u64 {
-    fn from(e: Expression) -> u64 {
-        // TODO: It would be ideal to use the Fn conversion and a way to "bind" `f` to u64::add.
-        //
-        // XXX: In fact more than ideal, this really should only be able to return an `Option<u64>`
-        // since there are lambda terms which can evaluate to something which is not a chruch
-        // encoded function.
-        match e.normalize(&Strategy::Applicative(true)) {
-            Expression::Var(id) => {
-                if id == variable!(f) { 1 } else { 0 }
-            },
-            Expression::Abs(Abstraction(Variable(_id, _ty), box body)) => {
-                u64::from(body)
-            },
-            Expression::App(Application(box e1, box e2)) => {
-                u64::from(e1) + u64::from(e2)
-            },
+impl TryFrom<Expression> for u64 {
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<u64, DecodeError> {
+        let e = normalize(e)?;
+        let (ids, mut body) = abstractions(&e, 2)?;
+        let (f, x) = (ids[0], ids[1]);
+
+        // When both are named the same, `f` is shadowed by `x`.
+        let mut n = 0;
+        while let Expression::App(Application(box Expression::Var(g), box e2)) = body {
+            if g.0 != f.0 || f.0 == x.0 {
+                break;
+            }
+            n += 1;
+            body = e2;
+        }
+
+        match body {
+            Expression::Var(y) if y.0 == x.0 => Ok(n),
+            _ => Err(DecodeError::NotANumeral(e.clone())),
         }
     }
 }
 
+/// Convert λ term back to native Rust type
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::{Byte, DecodeError, Expression};
+///
+/// assert_eq!(Ok(Byte(255)), Byte::try_from(Expression::from(255)));
+/// assert_eq!(Err(DecodeError::OutOfRange(Expression::from(256))),
+///            Byte::try_from(Expression::from(256)));
+/// ```
+impl TryFrom<Expression> for Byte {
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<Byte, DecodeError> {
+        let e = normalize(e)?;
+        let n = u64::try_from(e.clone())?;
+        u8::try_from(n).map(Byte).map_err(|_| DecodeError::OutOfRange(e))
+    }
+}
+
 /// ```
 /// # #![feature(box_syntax)]
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
 /// # fn main() {
+/// use std::convert::TryFrom;
+///
 /// let one = λ!{f.λ!{x.γ!(f,x)}};
 /// let two = one.clone() + one.clone();
-/// assert_eq!(2, u64::from(two.clone()));
-/// assert_eq!(4, u64::from(two.clone() + two.clone()));
+/// assert_eq!(Ok(2), u64::try_from(two.clone()));
+/// assert_eq!(Ok(4), u64::try_from(two.clone() + two.clone()));
 /// # }
 /// ```
 impl Add for Expression {
@@ -85,10 +143,12 @@ impl Add for Expression {
 /// # #[macro_use]
 /// # extern crate lalrpop_lambda;
 /// # fn main() {
+/// use std::convert::TryFrom;
+///
 /// let one = λ!{f.λ!{x.γ!(f,x)}};
 /// let two = one.clone() + one.clone();
-/// assert_eq!(1, u64::from(one.clone() * one.clone()));
-/// assert_eq!(4, u64::from(two.clone() * two.clone()));
+/// assert_eq!(Ok(1), u64::try_from(one.clone() * one.clone()));
+/// assert_eq!(Ok(4), u64::try_from(two.clone() * two.clone()));
 /// # }
 /// ```
 impl Mul for Expression {
@@ -100,30 +160,261 @@ impl Mul for Expression {
     }
 }
 
+/// Truncated subtraction, like `u64::saturating_sub`
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// # fn main() {
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::Expression;
+///
+/// assert_eq!(Ok(2), u64::try_from(Expression::from(5) - Expression::from(3)));
+/// assert_eq!(Ok(0), u64::try_from(Expression::from(3) - Expression::from(5)));
+/// # }
+/// ```
+impl Sub for Expression {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        γ!(γ!({sub()},{self}),{other}).normalize(&Strategy::Applicative(false))
+    }
+}
+
+/// Division, where dividing by zero gives zero instead of panicking
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// # fn main() {
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::Expression;
+///
+/// assert_eq!(Ok(2), u64::try_from(Expression::from(7) / Expression::from(3)));
+/// assert_eq!(Ok(0), u64::try_from(Expression::from(7) / Expression::from(0)));
+/// # }
+/// ```
+///
+/// # Evaluation Strategy
+///
+/// This is defined by recursion with the Y combinator, which only has a
+/// normal form under lazy evaluation, so it's evaluated by need, sharing the
+/// numerals being counted down. The recursion never ends when an operand
+/// isn't a numeral, so it's given steps enough for numerals the size of the
+/// operands, and gives the last term reached if they run out.
+impl Div for Expression {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        // λd.λm.λn.(leq n m) (succ (d (sub m n) n)) 0
+        let step = λ!{d.λ!{m.λ!{n.γ!(γ!(γ!(γ!({leq()},n),m),
+                                          γ!({succ()},γ!(γ!(d,γ!(γ!({sub()},m),n)),n))),
+                                       {zero()})}}};
+        let div = λ!{m.λ!{n.γ!(γ!(γ!({is_zero()},n),{zero()}),
+                               γ!(γ!(γ!({y()},{step}),m),n))}};
+        recurse(div, self, other)
+    }
+}
+
+/// Remainder, where the remainder of dividing by zero is the dividend instead
+/// of panicking
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// # fn main() {
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::Expression;
+///
+/// assert_eq!(Ok(1), u64::try_from(Expression::from(7) % Expression::from(3)));
+/// assert_eq!(Ok(7), u64::try_from(Expression::from(7) % Expression::from(0)));
+/// # }
+/// ```
+///
+/// # Evaluation Strategy
+///
+/// Like `Div`, this uses the Y combinator, and bounded call-by-need
+/// evaluation.
+impl Rem for Expression {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        // λr.λm.λn.(leq n m) (r (sub m n) n) m
+        let step = λ!{r.λ!{m.λ!{n.γ!(γ!(γ!(γ!({leq()},n),m),
+                                          γ!(γ!(r,γ!(γ!({sub()},m),n)),n)),
+                                       m)}}};
+        let rem = λ!{m.λ!{n.γ!(γ!(γ!({is_zero()},n),m),
+                               γ!(γ!(γ!({y()},{step}),m),n))}};
+        recurse(rem, self, other)
+    }
+}
+
+impl Expression {
+    /// Raise a church numeral to the power of another
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// # fn main() {
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::Expression;
+    ///
+    /// let two = Expression::from(2);
+    /// assert_eq!(Ok(8), u64::try_from(two.clone().church_pow(Expression::from(3))));
+    /// assert_eq!(Ok(1), u64::try_from(two.church_pow(Expression::from(0))));
+    /// # }
+    /// ```
+    pub fn church_pow(self, other: Self) -> Self {
+        let pow = λ!{m.λ!{n.λ!{f.λ!{x.γ!(γ!(γ!(n,m),f),x)}}}};
+        γ!(γ!({pow},{self}),{other}).normalize(&Strategy::Applicative(false))
+    }
+
+    /// The church boolean of whether a church numeral is zero
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// # fn main() {
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::Expression;
+    ///
+    /// assert_eq!(Ok(true), bool::try_from(Expression::from(0).church_is_zero()));
+    /// assert_eq!(Ok(false), bool::try_from(Expression::from(2).church_is_zero()));
+    /// # }
+    /// ```
+    pub fn church_is_zero(self) -> Self {
+        γ!({is_zero()},{self}).normalize(&Strategy::Applicative(false))
+    }
+
+    /// The church boolean of whether a church numeral is less than or equal
+    /// to another
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// # fn main() {
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::Expression;
+    ///
+    /// assert_eq!(Ok(true), bool::try_from(Expression::from(2).church_leq(Expression::from(3))));
+    /// assert_eq!(Ok(false), bool::try_from(Expression::from(3).church_leq(Expression::from(2))));
+    /// # }
+    /// ```
+    pub fn church_leq(self, other: Self) -> Self {
+        γ!(γ!({leq()},{self}),{other}).normalize(&Strategy::Applicative(false))
+    }
+
+    /// The church boolean of whether two church numerals are equal
+    ///
+    /// Unlike `==`, which compares terms, this compares the numbers they
+    /// encode, as a λ term.
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// # fn main() {
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::Expression;
+    ///
+    /// assert_eq!(Ok(true), bool::try_from(Expression::from(2).church_eq(Expression::from(2))));
+    /// assert_eq!(Ok(false), bool::try_from(Expression::from(2).church_eq(Expression::from(3))));
+    /// # }
+    /// ```
+    pub fn church_eq(self, other: Self) -> Self {
+        let and = λ!{p.λ!{q.γ!(γ!(p,q),p)}};
+        let eq = λ!{m.λ!{n.γ!(γ!({and},γ!(γ!({leq()},m),n)),γ!(γ!({leq()},n),m))}};
+        γ!(γ!({eq},{self}),{other}).normalize(&Strategy::Applicative(false))
+    }
+}
+
+/// Apply a recursive operation to `m` and `n`, and normalize it by need
+///
+/// Dividing `m` by a numeral takes around `8 m²` steps, and the size of `m`
+/// is over `2 m`, so steps of four times the operands' size squared are
+/// enough for any numerals.
+fn recurse(f: Expression, m: Expression, n: Expression) -> Expression {
+    let size = m.size() + n.size();
+    let limits = Limits {
+        steps: Some(4 * size * size),
+        ..Limits::default()
+    };
+    γ!(γ!({f},{m}),{n}).normalize_with_limits(&Strategy::CallByNeed, &limits)
+        .unwrap_or_else(|exhausted| exhausted.expression)
+}
+
+/// λf.λx.x
+fn zero() -> Expression {
+    λ!{f.λ!{x.x}}
+}
+
+/// λn.λf.λx.f (n f x)
+fn succ() -> Expression {
+    λ!{n.λ!{f.λ!{x.γ!(f, γ!(γ!(n, f), x))}}}
+}
+
+/// Kleene's predecessor, counting up pairs of numbers from `(0, 0)`
+///
+/// λn.n (λp.λq.q (p F) (succ (p F))) (λq.q 0 0) T
+fn pred() -> Expression {
+    let (t, f) = (λ!{a.λ!{b.a}}, λ!{a.λ!{b.b}});
+    let next = λ!{p.λ!{q.γ!(γ!(q,γ!(p,{&f})),γ!({succ()},γ!(p,{&f})))}};
+    let first = λ!{q.γ!(γ!(q,{zero()}),{zero()})};
+    λ!{n.γ!(γ!(γ!(n,{next}),{first}),{t})}
+}
+
+/// λm.λn.n pred m
+fn sub() -> Expression {
+    λ!{m.λ!{n.γ!(γ!(n,{pred()}),m)}}
+}
+
+/// λn.n (λx.F) T
+fn is_zero() -> Expression {
+    λ!{n.γ!(γ!(n,λ!{x.λ!{a.λ!{b.b}}}),λ!{a.λ!{b.a}})}
+}
+
+/// λm.λn.is_zero (sub m n)
+fn leq() -> Expression {
+    λ!{m.λ!{n.γ!({is_zero()},γ!(γ!({sub()},m),n))}}
+}
+
+/// λf.(λx.f (x x)) (λx.f (x x))
+fn y() -> Expression {
+    let half = λ!{x.γ!(f,γ!(x,x))};
+    λ!{f.γ!({&half},{&half})}
+}
+
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryInto;
     use pretty_assertions::assert_eq;
+    use crate::Limit;
     use crate::parse::ExpressionParser;
     use super::*;
 
-    // // TODO: Move these to tests as we finalize.
-    // dbg!(u64::from(var!(x)));
-    // dbg!(u64::from(var!(f)));
-    // dbg!(u64::from(app!(f,app!(f,x))));
-    // dbg!(u64::from(abs!{f.app!(f,app!(f,x))}));
-    // dbg!(u64::from(abs!{f.abs!{x.app!(f,app!(f,x))}}));
-
     #[test]
     fn u64() {
-        assert_eq!(0u64, Expression::from(0).into());
-        assert_eq!(5u64, Expression::from(5).into());
+        assert_eq!(Ok(0u64), Expression::from(0).try_into());
+        assert_eq!(Ok(5u64), Expression::from(5).try_into());
+        // Normal forms are decoded past the limits on reducing to them.
+        assert_eq!(Ok(1100u64), Expression::from(1100).try_into());
     }
 
     #[test]
     fn zero() {
-        // TODO: Should this be correct? What to do about smaller terms?
-        assert_eq!(0, u64::from(λ!{x.x}));
+        assert_eq!(Err(DecodeError::Arity { expected: 2, found: 1 }),
+                   u64::try_from(λ!{x.x}));
+        // Shadowing the `f` leaves only `x`.
+        assert_eq!(Ok(0), u64::try_from(λ!{x.λ!{x.x}}));
+        assert_eq!(Err(DecodeError::NotANumeral(λ!{x.λ!{x.γ!(x,x)}})),
+                   u64::try_from(λ!{x.λ!{x.γ!(x,x)}}));
     }
 
 
@@ -131,8 +422,38 @@ mod tests {
     fn one() {
         let ω = ExpressionParser::new().parse("λx.x x").unwrap();
 
-        // TODO: Should this be correct? What to do about smaller terms?
-        assert_eq!(1, u64::from(ω(Expression::from(1))));
+        assert_eq!(Ok(1), u64::try_from(ω(Expression::from(1))));
+    }
+
+    #[test]
+    fn not_a_numeral() {
+        assert_eq!(Err(DecodeError::Arity { expected: 2, found: 0 }),
+                   u64::try_from(var!(x)));
+        assert_eq!(Err(DecodeError::Arity { expected: 2, found: 3 }),
+                   u64::try_from(λ!{f.λ!{x.λ!{y.x}}}));
+        assert_eq!(Err(DecodeError::NotANumeral(λ!{f.λ!{x.γ!(f,f)}})),
+                   u64::try_from(λ!{f.λ!{x.γ!(f,f)}}));
+        assert_eq!(Err(DecodeError::NotANumeral(λ!{f.λ!{x.γ!(γ!(f,x),x)}})),
+                   u64::try_from(λ!{f.λ!{x.γ!(γ!(f,x),x)}}));
+    }
+
+    #[test]
+    fn diverged() {
+        let ω = ExpressionParser::new().parse("λx.x x").unwrap();
+
+        assert_eq!(Err(DecodeError::Diverged(γ!({&ω},{&ω}))),
+                   u64::try_from(γ!({&ω},{&ω})));
+
+        // (λx.x x x) (λx.x x x) grows until it's too large.
+        let ω3 = ExpressionParser::new().parse("λx.x x x").unwrap();
+        match u64::try_from(γ!({&ω3},{&ω3})) {
+            Err(DecodeError::Exhausted(e)) => assert!(match e.limit {
+                Limit::Size(limit) => e.expression.size() <= limit,
+                Limit::Depth(limit) => e.expression.depth() <= limit,
+                Limit::Steps(_) => false,
+            }),
+            result => panic!("expected exhaustion, found {:?}", result),
+        }
     }
 
     #[test]
@@ -146,5 +467,86 @@ mod tests {
         assert_eq!(Expression::from(6), Expression::from(2) * Expression::from(3));
     }
 
-    // app!(n, (\f.\x.(f (f x)))) -> 2^n
+    // Each operation against native `u64` arithmetic on every pair of `0..N`.
+    const N: u64 = 5;
+
+    fn table<F, G, T>(f: F, g: G)
+        where F: Fn(Expression, Expression) -> Expression,
+              G: Fn(u64, u64) -> Option<T>,
+              T: TryFrom<Expression, Error = DecodeError> + PartialEq + std::fmt::Debug
+    {
+        for m in 0..N {
+            for n in 0..N {
+                if let Some(expected) = g(m, n) {
+                    let e = f(Expression::from(m), Expression::from(n));
+                    assert_eq!(Ok(expected), T::try_from(e), "{} and {}", m, n);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn predecessor() {
+        for n in 0..N {
+            let pred = γ!({pred()},{Expression::from(n)});
+            assert_eq!(Ok(n.saturating_sub(1)), u64::try_from(pred));
+        }
+    }
+
+    #[test]
+    fn subtract() {
+        table(|m, n| m - n, |m, n| Some(m.saturating_sub(n)));
+    }
+
+    #[test]
+    fn pow() {
+        table(Expression::church_pow, |m, n| Some(m.pow(n as u32)));
+    }
+
+    #[test]
+    fn divide() {
+        table(|m, n| m / n, |m, n| m.checked_div(n));
+        assert_eq!(Expression::from(0), Expression::from(3) / Expression::from(0));
+    }
+
+    // Larger operands, in the tens and hundreds.
+    const LARGE: [(u64, u64); 8] = [(20, 3), (30, 7), (50, 7), (99, 10), (100, 1),
+                                    (123, 45), (250, 16), (300, 299)];
+
+    #[test]
+    fn divide_large() {
+        for &(m, n) in &LARGE {
+            let e = Expression::from(m) / Expression::from(n);
+            assert_eq!(Ok(m / n), u64::try_from(e), "{} / {}", m, n);
+        }
+    }
+
+    #[test]
+    fn remainder_large() {
+        for &(m, n) in &LARGE {
+            let e = Expression::from(m) % Expression::from(n);
+            assert_eq!(Ok(m % n), u64::try_from(e), "{} % {}", m, n);
+        }
+    }
+
+    #[test]
+    fn not_numerals() {
+        // The recursion never reaches a base case.
+        let a = var!(a);
+        assert!(u64::try_from(a.clone() / Expression::from(1)).is_err());
+        assert!(u64::try_from(a % Expression::from(1)).is_err());
+    }
+
+    #[test]
+    fn remainder() {
+        table(|m, n| m % n, |m, n| m.checked_rem(n));
+        assert_eq!(Expression::from(3), Expression::from(3) % Expression::from(0));
+    }
+
+    #[test]
+    fn compare() {
+        table(|m, _| m.church_is_zero(), |m, _| Some(m == 0));
+        table(Expression::church_leq, |m, n| Some(m <= n));
+        table(Expression::church_eq, |m, n| Some(m == n));
+    }
 }
@@ -0,0 +1,110 @@
+use std::convert::TryFrom;
+use crate::{Expression, Application};
+use super::{abstractions, binder, closed, normalize, DecodeError};
+
+/// Church encoded options
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use lalrpop_lambda::Expression;
+///
+/// # fn main() {
+/// let one = Expression::from(1);
+/// assert_eq!(λ!{n.λ!{s.n}}, Expression::from(None::<u64>));
+/// assert_eq!(λ!{n.λ!{s.γ!(s,{one})}}, Expression::from(Some(1u64)));
+/// # }
+/// ```
+impl<T> From<Option<T>> for Expression
+    where T: Into<Expression>
+{
+    fn from(option: Option<T>) -> Self {
+        match option {
+            None => λ!{n.λ!{s.n}},
+            Some(a) => {
+                let a = a.into();
+                let (n, s) = (binder("n", &[&a]), binder("s", &[&a]));
+                let body = γ!({Expression::Var(s.clone())},{a});
+                Expression::build_abs(1, vec![n, s], Some(body))
+            }
+        }
+    }
+}
+
+/// Convert λ term back to native Rust type
+///
+/// The term's normal form must be exactly `λn.λs.n`, or `λn.λs.s a` where `a`
+/// decodes to the type of the option.
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::DecodeError;
+///
+/// # fn main() {
+/// assert_eq!(Ok(None), Option::<bool>::try_from(λ!{n.λ!{s.n}}));
+/// assert_eq!(Ok(Some(true)), Option::<bool>::try_from(λ!{n.λ!{s.γ!(s,λ!{a.λ!{b.a}})}}));
+/// assert_eq!(Err(DecodeError::NotAnOption(λ!{n.λ!{s.s}})),
+///            Option::<bool>::try_from(λ!{n.λ!{s.s}}));
+/// # }
+/// ```
+impl<T> TryFrom<Expression> for Option<T>
+    where T: TryFrom<Expression, Error = DecodeError>
+{
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<Option<T>, DecodeError> {
+        let e = normalize(e)?;
+        let (ids, body) = abstractions(&e, 2)?;
+        let (n, s) = (ids[0], ids[1]);
+
+        // When both are named the same, `n` is shadowed by `s`.
+        match body {
+            Expression::Var(m) if m.0 == n.0 && n.0 != s.0 => Ok(None),
+            Expression::App(Application(box Expression::Var(t), box a))
+                if t.0 == s.0 && closed(a, &[n, s]) => {
+                Ok(Some(T::try_from(a.clone())?))
+            }
+            _ => Err(DecodeError::NotAnOption(e.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use pretty_assertions::assert_eq;
+    use crate::parse::ExpressionParser;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(Ok(None::<u64>), Expression::from(None::<u64>).try_into());
+        assert_eq!(Ok(Some(7u64)), Expression::from(Some(7u64)).try_into());
+        assert_eq!(Ok(Some(Some(false))),
+                   Expression::from(Some(Some(false))).try_into());
+    }
+
+    #[test]
+    fn map() {
+        let parser = ExpressionParser::new();
+        let map = parser.parse(r"λo.λn s.o n (λa.s (λf x.f (a f x)))").unwrap();
+
+        assert_eq!(Ok(Some(4u64)), map.clone()(Expression::from(Some(3u64))).try_into());
+        assert_eq!(Ok(None::<u64>), map(Expression::from(None::<u64>)).try_into());
+    }
+
+    #[test]
+    fn not_an_option() {
+        // Shadowing the `n` leaves only `s`.
+        assert_eq!(Err(DecodeError::NotAnOption(λ!{x.λ!{x.x}})),
+                   Option::<bool>::try_from(λ!{x.λ!{x.x}}));
+        assert_eq!(Err(DecodeError::NotAnOption(λ!{n.λ!{s.γ!(s,n)}})),
+                   Option::<bool>::try_from(λ!{n.λ!{s.γ!(s,n)}}));
+        assert_eq!(Err(DecodeError::NotABoolean(Expression::from(2))),
+                   Option::<bool>::try_from(Expression::from(Some(2u64))));
+    }
+}
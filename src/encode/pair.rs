@@ -0,0 +1,110 @@
+use std::convert::TryFrom;
+use crate::{Expression, Application};
+use super::{abstractions, binder, closed, normalize, DecodeError};
+
+/// Church encoded pairs
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use lalrpop_lambda::Expression;
+///
+/// # fn main() {
+/// let (one, t) = (Expression::from(1), Expression::from(true));
+/// assert_eq!(λ!{p.γ!(γ!(p,{one}),{t})}, Expression::from((1u64, true)));
+/// # }
+/// ```
+impl<A, B> From<(A, B)> for Expression
+    where A: Into<Expression>,
+          B: Into<Expression>
+{
+    fn from((a, b): (A, B)) -> Self {
+        let (a, b) = (a.into(), b.into());
+        let p = binder("p", &[&a, &b]);
+        let body = γ!(γ!({Expression::Var(p.clone())},{a}),{b});
+        Expression::build_abs(1, vec![p], Some(body))
+    }
+}
+
+/// Convert λ term back to native Rust type
+///
+/// The term's normal form must be exactly `λp.p a b`, where `a` and `b`
+/// decode to the types of the pair.
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::{DecodeError, Expression};
+///
+/// # fn main() {
+/// let pair = Expression::from((2u64, false));
+/// assert_eq!(Ok((2, false)), <(u64, bool)>::try_from(pair.clone()));
+/// assert_eq!(Err(DecodeError::NotABoolean(Expression::from(2))),
+///            <(bool, bool)>::try_from(pair));
+/// # }
+/// ```
+impl<A, B> TryFrom<Expression> for (A, B)
+    where A: TryFrom<Expression, Error = DecodeError>,
+          B: TryFrom<Expression, Error = DecodeError>
+{
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<(A, B), DecodeError> {
+        let e = normalize(e)?;
+        let (ids, body) = abstractions(&e, 1)?;
+        let p = ids[0];
+
+        match body {
+            Expression::App(Application(
+                box Expression::App(Application(box Expression::Var(q), box a)),
+                box b,
+            )) if q.0 == p.0 && closed(a, &[p]) && closed(b, &[p]) => {
+                Ok((A::try_from(a.clone())?, B::try_from(b.clone())?))
+            }
+            _ => Err(DecodeError::NotAPair(e.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use pretty_assertions::assert_eq;
+    use crate::parse::ExpressionParser;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(Ok((0u64, true)), Expression::from((0u64, true)).try_into());
+        assert_eq!(Ok(((1u64, 2u64), false)),
+                   Expression::from(((1u64, 2u64), false)).try_into());
+    }
+
+    #[test]
+    fn capture() {
+        // The pair's variable is renamed, so it doesn't bind the `p`s.
+        let pair = Expression::from((var!(p), var!(q)));
+        let body = γ!(γ!(var!("p'"),p),q);
+        assert_eq!(Expression::build_abs(1, vec![variable!("p'")], Some(body)), pair);
+    }
+
+    #[test]
+    fn swap() {
+        let swap = ExpressionParser::new().parse(r"λp.λq.q (p (λa b.b)) (p (λa b.a))").unwrap();
+        assert_eq!(Ok((true, 3u64)),
+                   swap(Expression::from((3u64, true))).try_into());
+    }
+
+    #[test]
+    fn not_a_pair() {
+        assert_eq!(Err(DecodeError::Arity { expected: 1, found: 2 }),
+                   <(bool, bool)>::try_from(Expression::from(true)));
+        assert_eq!(Err(DecodeError::NotAPair(λ!{p.γ!(p,p)})),
+                   <(bool, bool)>::try_from(λ!{p.γ!(p,p)}));
+        assert_eq!(Err(DecodeError::NotAPair(λ!{p.γ!(γ!(p,p),p)})),
+                   <(bool, bool)>::try_from(λ!{p.γ!(γ!(p,p),p)}));
+    }
+}
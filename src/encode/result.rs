@@ -0,0 +1,115 @@
+use std::convert::TryFrom;
+use crate::{Expression, Application};
+use super::{abstractions, binder, closed, normalize, DecodeError};
+
+/// Church encoded results
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use lalrpop_lambda::Expression;
+///
+/// # fn main() {
+/// let (one, t) = (Expression::from(1), Expression::from(true));
+/// assert_eq!(λ!{o.λ!{e.γ!(o,{one})}}, Expression::from(Ok::<u64, bool>(1)));
+/// assert_eq!(λ!{o.λ!{e.γ!(e,{t})}}, Expression::from(Err::<u64, bool>(true)));
+/// # }
+/// ```
+impl<T, E> From<Result<T, E>> for Expression
+    where T: Into<Expression>,
+          E: Into<Expression>
+{
+    fn from(result: Result<T, E>) -> Self {
+        let (a, ok) = match result {
+            Ok(a) => (a.into(), true),
+            Err(a) => (a.into(), false),
+        };
+        let (o, e) = (binder("o", &[&a]), binder("e", &[&a]));
+        let f = if ok { o.clone() } else { e.clone() };
+        let body = γ!({Expression::Var(f)},{a});
+        Expression::build_abs(1, vec![o, e], Some(body))
+    }
+}
+
+/// Convert λ term back to native Rust type
+///
+/// The term's normal form must be exactly `λo.λe.o a` or `λo.λe.e a`, where
+/// `a` decodes to the type of the result or error respectively.
+///
+/// ```
+/// # #![feature(box_syntax)]
+/// # #[macro_use]
+/// # extern crate lalrpop_lambda;
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::DecodeError;
+///
+/// # fn main() {
+/// let t = λ!{a.λ!{b.a}};
+/// assert_eq!(Ok(Ok(true)), Result::<bool, bool>::try_from(λ!{o.λ!{e.γ!(o,{&t})}}));
+/// assert_eq!(Ok(Err(true)), Result::<bool, bool>::try_from(λ!{o.λ!{e.γ!(e,{&t})}}));
+/// assert_eq!(Err(DecodeError::NotAResult(λ!{o.λ!{e.o}})),
+///            Result::<bool, bool>::try_from(λ!{o.λ!{e.o}}));
+/// # }
+/// ```
+impl<T, E> TryFrom<Expression> for Result<T, E>
+    where T: TryFrom<Expression, Error = DecodeError>,
+          E: TryFrom<Expression, Error = DecodeError>
+{
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<Result<T, E>, DecodeError> {
+        let e = normalize(e)?;
+        let (ids, body) = abstractions(&e, 2)?;
+        let (o, r) = (ids[0], ids[1]);
+
+        // When both are named the same, `o` is shadowed by `e`.
+        match body {
+            Expression::App(Application(box Expression::Var(f), box a))
+                if closed(a, &[o, r]) => {
+                if f.0 == r.0 {
+                    Ok(Err(E::try_from(a.clone())?))
+                } else if f.0 == o.0 {
+                    Ok(Ok(T::try_from(a.clone())?))
+                } else {
+                    Err(DecodeError::NotAResult(e.clone()))
+                }
+            }
+            _ => Err(DecodeError::NotAResult(e.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use pretty_assertions::assert_eq;
+    use crate::parse::ExpressionParser;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(Ok(Ok::<u64, bool>(2)), Expression::from(Ok::<u64, bool>(2)).try_into());
+        assert_eq!(Ok(Err::<u64, bool>(false)),
+                   Expression::from(Err::<u64, bool>(false)).try_into());
+    }
+
+    #[test]
+    fn unwrap_or() {
+        let parser = ExpressionParser::new();
+        let unwrap_or = parser.parse(r"λr d.r (λa.a) (λe.d)").unwrap();
+
+        let ok = Expression::from(Ok::<u64, bool>(3));
+        assert_eq!(Ok(3), u64::try_from(unwrap_or.clone()(ok)(0)));
+        let err = Expression::from(Err::<u64, bool>(true));
+        assert_eq!(Ok(0), u64::try_from(unwrap_or(err)(0)));
+    }
+
+    #[test]
+    fn not_a_result() {
+        assert_eq!(Err(DecodeError::NotAResult(λ!{o.λ!{e.γ!(a,o)}})),
+                   Result::<bool, bool>::try_from(λ!{o.λ!{e.γ!(a,o)}}));
+        assert_eq!(Err(DecodeError::NotAResult(λ!{o.λ!{e.γ!(o,e)}})),
+                   Result::<bool, bool>::try_from(λ!{o.λ!{e.γ!(o,e)}}));
+    }
+}
@@ -0,0 +1,116 @@
+use std::convert::TryFrom;
+use crate::Expression;
+use super::{normalize, Binary, DecodeError};
+
+/// Church encoded characters, as the binary numeral of their code point
+///
+/// A church numeral would nest as deep as the code point, which is too deep
+/// to normalize for most of Unicode.
+///
+/// ```
+/// use lalrpop_lambda::{Binary, Expression};
+///
+/// assert_eq!(Expression::from(Binary(97)), Expression::from('a'));
+/// ```
+impl From<char> for Expression {
+    fn from(c: char) -> Self {
+        Expression::from(Binary(u64::from(c)))
+    }
+}
+
+/// Convert λ term back to native Rust type
+///
+/// Binary numerals which aren't the code point of a `char` are `OutOfRange`.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::{Binary, Expression};
+///
+/// assert_eq!(Ok('a'), char::try_from(Expression::from(Binary(97))));
+/// ```
+impl TryFrom<Expression> for char {
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<char, DecodeError> {
+        let e = normalize(e)?;
+        let Binary(n) = Binary::try_from(e.clone())?;
+        u32::try_from(n).ok()
+            .and_then(std::char::from_u32)
+            .ok_or(DecodeError::OutOfRange(e))
+    }
+}
+
+/// Church encoded strings, as lists of characters
+///
+/// ```
+/// use lalrpop_lambda::Expression;
+///
+/// assert_eq!(Expression::from(vec!['h', 'i']), Expression::from("hi"));
+/// ```
+impl From<&str> for Expression {
+    fn from(s: &str) -> Self {
+        Expression::from(s.chars().collect::<Vec<_>>())
+    }
+}
+
+impl From<String> for Expression {
+    fn from(s: String) -> Self {
+        Expression::from(s.as_str())
+    }
+}
+
+/// Convert λ term back to native Rust type
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::Expression;
+///
+/// assert_eq!(Ok("hi".to_string()), String::try_from(Expression::from("hi")));
+/// ```
+impl TryFrom<Expression> for String {
+    type Error = DecodeError;
+
+    fn try_from(e: Expression) -> Result<String, DecodeError> {
+        Vec::<char>::try_from(e).map(|chars| chars.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use pretty_assertions::assert_eq;
+    use crate::parse::ExpressionParser;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(Ok('λ'), Expression::from('λ').try_into());
+        assert_eq!(Ok('😀'), Expression::from('😀').try_into());
+        assert_eq!(Ok(char::MAX), Expression::from(char::MAX).try_into());
+        assert_eq!(Ok(String::new()), Expression::from("").try_into());
+        assert_eq!(Ok("λx.x".to_string()), Expression::from("λx.x").try_into());
+
+        // Long strings are deeper and larger than the limits on normalizing.
+        let long: String = "λx.x 😀 ".chars().cycle().take(500).collect();
+        assert_eq!(Ok(long.clone()), Expression::from(long.as_str()).try_into());
+    }
+
+    #[test]
+    fn map() {
+        // Each character's successor.
+        let parser = ExpressionParser::new();
+        let next = parser.parse(r"λsucc s.λc n.s (λa.c (succ a)) n").unwrap();
+        let next = next(Binary::succ());
+        assert_eq!(Ok("IBM".to_string()), next(Expression::from("HAL")).try_into());
+    }
+
+    #[test]
+    fn not_a_string() {
+        assert_eq!(Err(DecodeError::NotAList(Expression::from(1))),
+                   String::try_from(Expression::from(1)));
+        assert_eq!(Err(DecodeError::Arity { expected: 3, found: 2 }),
+                   String::try_from(Expression::from(vec![true])));
+        assert_eq!(Err(DecodeError::OutOfRange(Expression::from(Binary(0x110000)))),
+                   char::try_from(Expression::from(Binary(0x110000))));
+    }
+}
@@ -0,0 +1,542 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::{Abstraction, Application, Exhausted, Expression, Limit, Limits, Stats, Variable};
+
+/// An argument shared by every occurrence of its variable, and updated in
+/// place with its value the first time it's needed
+type Thunk<'a> = Rc<RefCell<State<'a>>>;
+
+enum State<'a> {
+    /// A term in its environment, applied to the arguments it has yet to
+    /// take, either not yet evaluated or stopped partway by the limits
+    Delayed(&'a Expression, Env<'a>, Vec<Thunk<'a>>),
+    Forced(Value<'a>),
+}
+
+/// An evaluation which ran out of limits, and the state it reached
+struct Stop<'a> {
+    limit: Limit,
+    state: State<'a>,
+}
+
+/// The thunk bound to each variable in scope, innermost first
+#[derive(Clone, Default)]
+struct Env<'a>(Option<Rc<Binding<'a>>>);
+
+struct Binding<'a> {
+    name: &'a str,
+    thunk: Thunk<'a>,
+    next: Env<'a>,
+}
+
+/// A weak head normal form
+#[derive(Clone)]
+enum Value<'a> {
+    Closure(&'a Variable, &'a Expression, Env<'a>),
+    /// A variable without a binding, applied to arguments
+    Neutral(Rc<str>, Vec<Thunk<'a>>),
+}
+
+impl<'a> Env<'a> {
+    fn bind(&self, name: &'a str, thunk: Thunk<'a>) -> Self {
+        Env(Some(Rc::new(Binding {
+            name,
+            thunk,
+            next: self.clone(),
+        })))
+    }
+
+    fn lookup(&self, name: &str) -> Option<Thunk<'a>> {
+        let mut env = self;
+        while let Env(Some(binding)) = env {
+            if binding.name == name {
+                return Some(binding.thunk.clone());
+            }
+            env = &binding.next;
+        }
+        None
+    }
+}
+
+fn thunk<'a>(state: State<'a>) -> Thunk<'a> {
+    Rc::new(RefCell::new(state))
+}
+
+/// Lazy evaluation with sharing, which reads values back into terms under
+/// their binders to reach a normal form
+struct Machine<'l> {
+    limits: &'l Limits,
+    steps: usize,
+    depth: usize,
+    /// The number of binders renamed when reading back
+    renames: usize,
+}
+
+impl<'l> Machine<'l> {
+    // Evaluate to a weak head normal form, keeping the arguments waiting for
+    // the operator on a stack, so long reductions don't recurse. When the
+    // limits run out, the state reached is returned to be read back.
+    fn eval<'a>(
+        &mut self,
+        mut e: &'a Expression,
+        mut env: Env<'a>,
+        mut arguments: Vec<Thunk<'a>>,
+    ) -> Result<Value<'a>, Stop<'a>> {
+        loop {
+            let value = match e {
+                Expression::App(Application(e1, e2)) => {
+                    arguments.push(thunk(State::Delayed(e2, env.clone(), vec![])));
+                    e = e1;
+                    continue;
+                }
+                Expression::Abs(Abstraction(id, body)) => Value::Closure(id, body, env.clone()),
+                Expression::Var(id) => match env.lookup(&id.0) {
+                    Some(thunk) => match self.force(&thunk) {
+                        Ok(value) => value,
+                        Err(limit) => {
+                            return Err(Stop {
+                                limit,
+                                state: State::Delayed(e, env, arguments),
+                            })
+                        }
+                    },
+                    None => Value::Neutral(Rc::from(id.0.as_str()), vec![]),
+                },
+            };
+
+            match value {
+                Value::Closure(id, body, closure) => match arguments.pop() {
+                    Some(argument) => {
+                        if let Err(limit) = self.step() {
+                            arguments.push(argument);
+                            return Err(Stop {
+                                limit,
+                                state: State::Delayed(e, env, arguments),
+                            });
+                        }
+                        env = closure.bind(&id.0, argument);
+                        e = body;
+                    }
+                    None => return Ok(Value::Closure(id, body, closure)),
+                },
+                Value::Neutral(id, mut applied) => {
+                    applied.extend(arguments.drain(..).rev());
+                    return Ok(Value::Neutral(id, applied));
+                }
+            }
+        }
+    }
+
+    // Evaluate a thunk the first time it's needed, keeping the state reached
+    // in it if the limits run out.
+    fn force<'a>(&mut self, thunk: &Thunk<'a>) -> Result<Value<'a>, Limit> {
+        let (e, env, arguments) = match &*thunk.borrow() {
+            State::Forced(value) => return Ok(value.clone()),
+            State::Delayed(e, env, arguments) => (*e, env.clone(), arguments.clone()),
+        };
+        self.enter()?;
+        match self.eval(e, env, arguments) {
+            Ok(value) => {
+                self.depth -= 1;
+                *thunk.borrow_mut() = State::Forced(value.clone());
+                Ok(value)
+            }
+            Err(stop) => {
+                *thunk.borrow_mut() = stop.state;
+                Err(stop.limit)
+            }
+        }
+    }
+
+    // Read a value back as a term in normal form, naming the variable of
+    // each closure apart from the names in `scope`.
+    fn read<'a>(
+        &mut self,
+        value: Value<'a>,
+        scope: &mut HashSet<String>,
+    ) -> Result<Expression, Limit> {
+        self.enter()?;
+        let e = match value {
+            Value::Closure(id, body, env) => {
+                let mut name = id.0.clone();
+                if scope.contains(&name) {
+                    self.renames += 1;
+                }
+                while scope.contains(&name) {
+                    name.push('\'');
+                }
+                scope.insert(name.clone());
+                let variable = thunk(State::Forced(Value::Neutral(
+                    Rc::from(name.as_str()),
+                    vec![],
+                )));
+                let value = self
+                    .eval(body, env.bind(&id.0, variable), vec![])
+                    .map_err(|stop| stop.limit)?;
+                let body = self.read(value, scope)?;
+                scope.remove(&name);
+                Expression::Abs(Abstraction(Variable(name, id.1.clone()), Box::new(body)))
+            }
+            Value::Neutral(id, arguments) => {
+                let mut e = Expression::Var(Variable(id.to_string(), None));
+                for argument in arguments {
+                    let value = self.force(&argument)?;
+                    let argument = self.read(value, scope)?;
+                    e = Expression::App(Application(Box::new(e), Box::new(argument)));
+                }
+                e
+            }
+        };
+        self.depth -= 1;
+        Ok(e)
+    }
+
+    fn step(&mut self) -> Result<(), Limit> {
+        if self.limits.steps == Some(self.steps) {
+            return Err(Limit::Steps(self.steps));
+        }
+        self.steps += 1;
+        Ok(())
+    }
+
+    fn enter(&mut self) -> Result<(), Limit> {
+        match self.limits.depth {
+            Some(depth) if self.depth == depth => Err(Limit::Depth(depth)),
+            _ => {
+                self.depth += 1;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reads a graph back as a term as it is, without evaluating it any further
+///
+/// Every thunk is read back once, those used more than once as the argument of
+/// a β-redex around the whole term, so sharing doesn't blow up its size.
+struct Quote<'a> {
+    /// The number of uses of each thunk, and the name of the first variable
+    /// it was used by
+    uses: HashMap<*const RefCell<State<'a>>, (usize, &'a str)>,
+    /// Every thunk, each after the thunks it uses
+    thunks: Vec<Thunk<'a>>,
+    /// The variable bound to each shared thunk
+    names: HashMap<*const RefCell<State<'a>>, String>,
+    /// The names which binders must avoid
+    scope: HashSet<String>,
+}
+
+impl<'a> Quote<'a> {
+    fn count_state(&mut self, state: &State<'a>) {
+        match state {
+            State::Delayed(e, env, arguments) => {
+                self.count(e, env, &mut vec![]);
+                for argument in arguments {
+                    self.use_thunk(argument, "x");
+                }
+            }
+            State::Forced(Value::Closure(id, body, env)) => {
+                self.count(body, env, &mut vec![&id.0]);
+            }
+            State::Forced(Value::Neutral(_, arguments)) => {
+                for argument in arguments {
+                    self.use_thunk(argument, "x");
+                }
+            }
+        }
+    }
+
+    fn count(&mut self, e: &'a Expression, env: &Env<'a>, bound: &mut Vec<&'a str>) {
+        match e {
+            Expression::Var(id) => {
+                if !bound.contains(&id.0.as_str()) {
+                    if let Some(thunk) = env.lookup(&id.0) {
+                        self.use_thunk(&thunk, &id.0);
+                    }
+                }
+            }
+            Expression::Abs(Abstraction(id, body)) => {
+                bound.push(&id.0);
+                self.count(body, env, bound);
+                bound.pop();
+            }
+            Expression::App(Application(e1, e2)) => {
+                self.count(e1, env, bound);
+                self.count(e2, env, bound);
+            }
+        }
+    }
+
+    fn use_thunk(&mut self, thunk: &Thunk<'a>, name: &'a str) {
+        let uses = self.uses.entry(Rc::as_ptr(thunk)).or_insert((0, name));
+        uses.0 += 1;
+        if uses.0 == 1 {
+            self.count_state(&thunk.borrow());
+            self.thunks.push(thunk.clone());
+        }
+    }
+
+    fn fresh(&mut self, name: &str) -> String {
+        let mut name = name.to_string();
+        while self.scope.contains(&name) {
+            name.push('\'');
+        }
+        self.scope.insert(name.clone());
+        name
+    }
+
+    fn quote_thunk(&mut self, thunk: &Thunk<'a>) -> Expression {
+        match self.names.get(&Rc::as_ptr(thunk)) {
+            Some(name) => Expression::Var(Variable(name.clone(), None)),
+            None => self.quote_state(&thunk.borrow()),
+        }
+    }
+
+    fn quote_state(&mut self, state: &State<'a>) -> Expression {
+        let (e, arguments) = match state {
+            State::Delayed(e, env, arguments) => (self.quote(e, env, &mut vec![]), arguments),
+            State::Forced(Value::Closure(id, body, env)) => {
+                (self.quote_abstraction(id, body, env, &mut vec![]), &vec![])
+            }
+            State::Forced(Value::Neutral(id, arguments)) => {
+                (Expression::Var(Variable(id.to_string(), None)), arguments)
+            }
+        };
+        arguments.iter().fold(e, |e, argument| {
+            Expression::App(Application(
+                Box::new(e),
+                Box::new(self.quote_thunk(argument)),
+            ))
+        })
+    }
+
+    // Quote a term in its environment, with the names given to the binders
+    // around it in `bound`, innermost last.
+    fn quote(
+        &mut self,
+        e: &'a Expression,
+        env: &Env<'a>,
+        bound: &mut Vec<(&'a str, String)>,
+    ) -> Expression {
+        match e {
+            Expression::Var(id) => match bound.iter().rev().find(|(old, _)| *old == id.0) {
+                Some((_, name)) => Expression::Var(Variable(name.clone(), id.1.clone())),
+                None => match env.lookup(&id.0) {
+                    Some(thunk) => self.quote_thunk(&thunk),
+                    None => e.clone(),
+                },
+            },
+            Expression::Abs(Abstraction(id, body)) => self.quote_abstraction(id, body, env, bound),
+            Expression::App(Application(e1, e2)) => Expression::App(Application(
+                Box::new(self.quote(e1, env, bound)),
+                Box::new(self.quote(e2, env, bound)),
+            )),
+        }
+    }
+
+    fn quote_abstraction(
+        &mut self,
+        id: &'a Variable,
+        body: &'a Expression,
+        env: &Env<'a>,
+        bound: &mut Vec<(&'a str, String)>,
+    ) -> Expression {
+        let name = self.fresh(&id.0);
+        bound.push((&id.0, name.clone()));
+        let body = self.quote(body, env, bound);
+        bound.pop();
+        self.scope.remove(&name);
+        Expression::Abs(Abstraction(Variable(name, id.1.clone()), Box::new(body)))
+    }
+}
+
+/// Read back the state reached by evaluating `e`
+fn quote(e: &Expression, root: &State) -> Expression {
+    let mut quote = Quote {
+        uses: HashMap::new(),
+        thunks: vec![],
+        names: HashMap::new(),
+        scope: e.free_variables().into_iter().map(|v| v.0).collect(),
+    };
+    quote.count_state(root);
+
+    let shared: Vec<_> = std::mem::take(&mut quote.thunks)
+        .into_iter()
+        .filter(|thunk| quote.uses[&Rc::as_ptr(thunk)].0 > 1)
+        .collect();
+    // Each shared thunk only uses those bound outside it.
+    let mut values = vec![];
+    for thunk in &shared {
+        values.push(quote.quote_state(&thunk.borrow()));
+        let name = quote.fresh(quote.uses[&Rc::as_ptr(thunk)].1);
+        quote.names.insert(Rc::as_ptr(thunk), name);
+    }
+
+    let body = quote.quote_state(root);
+    shared
+        .iter()
+        .zip(values)
+        .rev()
+        .fold(body, |body, (thunk, value)| {
+            let name = quote.names[&Rc::as_ptr(thunk)].clone();
+            Expression::App(Application(
+                Box::new(Expression::Abs(Abstraction(
+                    Variable(name, None),
+                    Box::new(body),
+                ))),
+                Box::new(value),
+            ))
+        })
+}
+
+impl Expression {
+    /// The normal form by call-by-need evaluation, see
+    /// [`Strategy::CallByNeed`](crate::Strategy::CallByNeed)
+    ///
+    /// `steps` bounds the number of β-reductions, `depth` bounds the nesting
+    /// of thunks being forced and terms being read back, and `size` bounds
+    /// the normal form. When they run out, the graph is read back as it was
+    /// left, without evaluating it further.
+    pub(crate) fn call_by_need(
+        &self,
+        limits: &Limits,
+        stats: &mut Stats,
+    ) -> Result<Expression, Exhausted> {
+        let mut machine = Machine {
+            limits,
+            steps: 0,
+            depth: 0,
+            renames: 0,
+        };
+        let mut scope = self.free_variables().into_iter().map(|v| v.0).collect();
+        let e = match machine.eval(self, Env::default(), vec![]) {
+            Ok(value) => match machine.read(value.clone(), &mut scope) {
+                Ok(e) => match limits.size {
+                    Some(limit) if e.size() > limit => {
+                        Err((Limit::Size(limit), State::Forced(value)))
+                    }
+                    _ => Ok(e),
+                },
+                Err(limit) => Err((limit, State::Forced(value))),
+            },
+            Err(stop) => Err((stop.limit, stop.state)),
+        };
+        stats.beta_steps += machine.steps;
+        stats.renames += machine.renames;
+
+        match e {
+            Ok(e) => {
+                stats.reach(e.size(), e.depth());
+                Ok(e)
+            }
+            Err((limit, state)) => Err(Exhausted {
+                limit,
+                expression: quote(self, &state),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use crate::parse::ExpressionParser;
+    use crate::{Expression, Limit, Limits, Prelude, Stats, Strategy};
+
+    fn steps(e: &Expression, strategy: &Strategy, steps: usize) -> Result<Expression, Limit> {
+        let limits = Limits {
+            steps: Some(steps),
+            ..Limits::default()
+        };
+        e.normalize_with_limits(strategy, &limits)
+            .map_err(|e| e.limit)
+    }
+
+    #[test]
+    fn sharing() {
+        let prelude = Prelude::new();
+        let e = prelude
+            .parse("two = succ (succ zero); (λn.add n n) (pow two (succ two))")
+            .unwrap();
+
+        // Normal order reduces each copy of `n` again.
+        assert_eq!(
+            Err(Limit::Steps(150)),
+            steps(&e, &Strategy::Normal(false), 150)
+        );
+        let normal = steps(&e, &Strategy::CallByNeed, 150).unwrap();
+        assert_eq!(Ok(16), u64::try_from(normal));
+    }
+
+    #[test]
+    fn names() {
+        let parser = ExpressionParser::new();
+        let normalize = |source: &str| {
+            parser
+                .parse(source)
+                .unwrap()
+                .normalize(&Strategy::CallByNeed)
+                .to_string()
+        };
+
+        assert_eq!("λx x'.x", normalize("λx.(λy.λx.y) x"));
+        assert_eq!("λx'.x x'", normalize("(λy.λx.y x) x"));
+        assert_eq!("λx x'.x'", normalize("λx.λx.x"));
+        assert_eq!("a (λx.x)", normalize("(λf.f (λx.x)) a"));
+    }
+
+    #[test]
+    fn partial() {
+        let parser = ExpressionParser::new();
+        let exhausted = |source, steps| {
+            let limits = Limits {
+                steps: Some(steps),
+                ..Limits::default()
+            };
+            parser
+                .parse(source)
+                .unwrap()
+                .normalize_with_limits(&Strategy::CallByNeed, &limits)
+                .unwrap_err()
+                .expression
+                .to_string()
+        };
+
+        let source = r"(λx.x (x b)) ((λy.λz.y z) ((λw.w) a))";
+        assert_eq!("(λx.x (x b)) (λz.(λw.w) a z)", exhausted(source, 2));
+        // The argument shared by both uses of `y` is bound once.
+        assert_eq!("(λy.y ((λz.y z) b)) ((λw.w) a)", exhausted(source, 3));
+        assert_eq!("a ((λz.a z) b)", exhausted(source, 4));
+        assert_eq!("λy.(λz.z z) y", exhausted(r"(λx.λy.x y) (λz.z z)", 1));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn limits() {
+        let parser = ExpressionParser::new();
+        let Ω = parser.parse("(λx.x x) (λx.x x)").unwrap();
+        assert_eq!(
+            Err(Limit::Steps(1000)),
+            steps(&Ω, &Strategy::CallByNeed, 1000)
+        );
+
+        let three = Expression::from(3u64);
+        let need = |size, depth| {
+            let limits = Limits {
+                size,
+                depth,
+                ..Limits::default()
+            };
+            three
+                .call_by_need(&limits, &mut Stats::default())
+                .map_err(|exhausted| exhausted.limit)
+        };
+        let size = three.size();
+        assert_eq!(Ok(three.clone()), need(Some(size), Some(6)));
+        assert_eq!(Err(Limit::Size(size - 1)), need(Some(size - 1), None));
+        assert_eq!(Err(Limit::Depth(5)), need(None, Some(5)));
+    }
+}
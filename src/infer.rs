@@ -0,0 +1,442 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::{Abstraction, Application, Context, Expression, Type, TypeError};
+
+/// A principal type, with its type variables universally quantified
+///
+/// ```
+/// use lalrpop_lambda::{Scheme, Type};
+///
+/// let a = || Box::new(Type::Base("a".into()));
+/// let id = Scheme(vec!["a".into()], Type::Arrow(a(), a()));
+/// assert_eq!("∀a. a → a", id.to_string());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Scheme(pub Vec<String>, pub Type);
+
+impl Expression {
+    /// Infer the most general type of an expression, with algorithm W
+    ///
+    /// Annotations are optional, and annotated types are treated as
+    /// constants, as are the base types of the context. Since `let x = e1 in
+    /// e2` is parsed as `(λx.e2) e1`, every such redex is typed like a `let`,
+    /// with `x` given a polymorphic type.
+    ///
+    /// ```
+    /// use lalrpop_lambda::{Context, TypeError};
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let infer = |source| {
+    ///     parser.parse(source).unwrap().infer(&Context::new()).map(|s| s.to_string())
+    /// };
+    ///
+    /// assert_eq!(Ok("∀a b. (a → b) → a → b".into()), infer(r"λf x.f x"));
+    /// assert_eq!(Ok("∀a. a → a".into()), infer(r"let id = λx.x in id id"));
+    /// assert_eq!(Ok("b → b".into()), infer(r"λx:b.x"));
+    ///
+    /// let error = infer(r"λx.x x").unwrap_err();
+    /// assert_eq!("x x needs the infinite type a = a → b", error.to_string());
+    /// ```
+    pub fn infer(&self, context: &Context) -> Result<Scheme, TypeError> {
+        let mut w = W::default();
+        let env = context
+            .iter()
+            .map(|(id, ty)| (id.clone(), (vec![], Term::from(ty))))
+            .collect();
+        let term = w.infer(&env, self)?;
+        let term = w.resolve(&term);
+        let mut names = Names::new(&[&term]);
+        let ty = names.get(&term);
+        Ok(Scheme(names.vars, ty))
+    }
+}
+
+/// A type during inference, with variables for the parts not yet known
+#[derive(Clone, Debug)]
+enum Term {
+    Var(usize),
+    Base(String),
+    Arrow(Box<Term>, Box<Term>),
+}
+
+impl From<&Type> for Term {
+    fn from(ty: &Type) -> Self {
+        match ty {
+            Type::Base(name) => Term::Base(name.clone()),
+            Type::Arrow(t1, t2) => {
+                Term::Arrow(Box::new(Term::from(&**t1)), Box::new(Term::from(&**t2)))
+            }
+        }
+    }
+}
+
+impl Term {
+    fn arrow(t1: Term, t2: Term) -> Self {
+        Term::Arrow(Box::new(t1), Box::new(t2))
+    }
+
+    fn vars(&self, vars: &mut Vec<usize>) {
+        match self {
+            Term::Var(v) if !vars.contains(v) => vars.push(*v),
+            Term::Var(_) | Term::Base(_) => {}
+            Term::Arrow(t1, t2) => {
+                t1.vars(vars);
+                t2.vars(vars);
+            }
+        }
+    }
+}
+
+/// A type term with some of its variables quantified
+type Poly = (Vec<usize>, Term);
+
+/// The ways two terms fail to unify
+enum Clash {
+    Mismatch,
+    Occurs(usize, Term),
+}
+
+/// The state of algorithm W, the substitution found so far
+#[derive(Default)]
+struct W {
+    substitution: Vec<Option<Term>>,
+}
+
+impl W {
+    fn fresh(&mut self) -> Term {
+        self.substitution.push(None);
+        Term::Var(self.substitution.len() - 1)
+    }
+
+    /// Apply the substitution to a term, fully
+    fn resolve(&self, term: &Term) -> Term {
+        match term {
+            Term::Var(v) => match &self.substitution[*v] {
+                Some(term) => self.resolve(term),
+                None => term.clone(),
+            },
+            Term::Base(_) => term.clone(),
+            Term::Arrow(t1, t2) => Term::arrow(self.resolve(t1), self.resolve(t2)),
+        }
+    }
+
+    fn unify(&mut self, t1: &Term, t2: &Term) -> Result<(), Clash> {
+        match (self.resolve(t1), self.resolve(t2)) {
+            (Term::Var(v1), Term::Var(v2)) if v1 == v2 => Ok(()),
+            (Term::Var(v), term) | (term, Term::Var(v)) => {
+                let mut vars = vec![];
+                term.vars(&mut vars);
+                if vars.contains(&v) {
+                    Err(Clash::Occurs(v, term))
+                } else {
+                    self.substitution[v] = Some(term);
+                    Ok(())
+                }
+            }
+            (Term::Base(b1), Term::Base(b2)) if b1 == b2 => Ok(()),
+            (Term::Arrow(a1, r1), Term::Arrow(a2, r2)) => {
+                self.unify(&a1, &a2)?;
+                self.unify(&r1, &r2)
+            }
+            _ => Err(Clash::Mismatch),
+        }
+    }
+
+    /// Quantify the variables of `term` which aren't free in `env`
+    fn generalize(&self, env: &HashMap<String, Poly>, term: &Term) -> Poly {
+        let mut bound = vec![];
+        for (quantified, term) in env.values() {
+            let mut vars = vec![];
+            self.resolve(term).vars(&mut vars);
+            bound.extend(vars.into_iter().filter(|v| !quantified.contains(v)));
+        }
+
+        let term = self.resolve(term);
+        let mut vars = vec![];
+        term.vars(&mut vars);
+        vars.retain(|v| !bound.contains(v));
+        (vars, term)
+    }
+
+    fn instantiate(&mut self, (vars, term): &Poly) -> Term {
+        let fresh: HashMap<usize, Term> = vars.iter().map(|v| (*v, self.fresh())).collect();
+        fn replace(term: &Term, fresh: &HashMap<usize, Term>) -> Term {
+            match term {
+                Term::Var(v) => fresh.get(v).cloned().unwrap_or_else(|| term.clone()),
+                Term::Base(_) => term.clone(),
+                Term::Arrow(t1, t2) => Term::arrow(replace(t1, fresh), replace(t2, fresh)),
+            }
+        }
+        replace(term, &fresh)
+    }
+
+    /// Unify `expected` with `found`, the type of `expression`
+    fn expect(
+        &mut self,
+        expression: &Expression,
+        expected: &Term,
+        found: &Term,
+    ) -> Result<(), TypeError> {
+        self.unify(expected, found)
+            .map_err(|clash| self.error(expression, clash, expected, found))
+    }
+
+    fn error(
+        &self,
+        expression: &Expression,
+        clash: Clash,
+        expected: &Term,
+        found: &Term,
+    ) -> TypeError {
+        match clash {
+            Clash::Mismatch => {
+                let (expected, found) = (self.resolve(expected), self.resolve(found));
+                let mut names = Names::new(&[&expected, &found]);
+                TypeError::Mismatch {
+                    expression: expression.clone(),
+                    expected: names.get(&expected),
+                    found: names.get(&found),
+                }
+            }
+            Clash::Occurs(v, term) => {
+                let (variable, term) = (Term::Var(v), self.resolve(&term));
+                let mut names = Names::new(&[&variable, &term]);
+                TypeError::Occurs {
+                    expression: expression.clone(),
+                    variable: names.get(&variable),
+                    ty: names.get(&term),
+                }
+            }
+        }
+    }
+
+    fn infer(&mut self, env: &HashMap<String, Poly>, e: &Expression) -> Result<Term, TypeError> {
+        match e {
+            Expression::Var(id) => {
+                let term = match env.get(&id.0) {
+                    Some(poly) => self.instantiate(poly),
+                    None => match &id.1 {
+                        Some(ty) => return Ok(Term::from(ty)),
+                        None => return Err(TypeError::Unbound(id.clone())),
+                    },
+                };
+                if let Some(ty) = &id.1 {
+                    let annotation = Term::from(ty);
+                    self.expect(e, &term, &annotation)?;
+                }
+                Ok(term)
+            }
+            Expression::Abs(Abstraction(id, body)) => {
+                let term = match &id.1 {
+                    Some(ty) => Term::from(ty),
+                    None => self.fresh(),
+                };
+                let mut env = env.clone();
+                env.insert(id.0.clone(), (vec![], term.clone()));
+                Ok(Term::arrow(term, self.infer(&env, body)?))
+            }
+            // A `let`, with the bound variable generalized.
+            Expression::App(Application(box Expression::Abs(Abstraction(id, body)), e1)) => {
+                let term = self.infer(env, e1)?;
+                if let Some(ty) = &id.1 {
+                    let annotation = Term::from(ty);
+                    self.expect(e1, &annotation, &term)?;
+                }
+                let mut env = env.clone();
+                let poly = self.generalize(&env, &term);
+                env.insert(id.0.clone(), poly);
+                self.infer(&env, body)
+            }
+            Expression::App(Application(e1, e2)) => {
+                let function = self.infer(env, e1)?;
+                let (domain, range) = (self.fresh(), self.fresh());
+                let arrow = Term::arrow(domain.clone(), range.clone());
+                if self.unify(&function, &arrow).is_err() {
+                    let found = self.resolve(&function);
+                    let mut names = Names::new(&[&found]);
+                    return Err(TypeError::NotAFunction {
+                        expression: *e1.clone(),
+                        found: names.get(&found),
+                    });
+                }
+
+                let argument = self.infer(env, e2)?;
+                match self.unify(&domain, &argument) {
+                    Ok(()) => Ok(range),
+                    Err(clash @ Clash::Mismatch) => Err(self.error(e2, clash, &domain, &argument)),
+                    Err(clash) => Err(self.error(e, clash, &domain, &argument)),
+                }
+            }
+        }
+    }
+}
+
+/// Names for type variables, `a`, `b`, … in order of appearance, skipping the
+/// names of base types
+struct Names {
+    vars: Vec<String>,
+    names: HashMap<usize, String>,
+    taken: HashSet<String>,
+}
+
+impl Names {
+    fn new(terms: &[&Term]) -> Self {
+        fn bases(term: &Term, taken: &mut HashSet<String>) {
+            match term {
+                Term::Var(_) => {}
+                Term::Base(name) => {
+                    taken.insert(name.clone());
+                }
+                Term::Arrow(t1, t2) => {
+                    bases(t1, taken);
+                    bases(t2, taken);
+                }
+            }
+        }
+
+        let mut taken = HashSet::new();
+        for term in terms {
+            bases(term, &mut taken);
+        }
+        Names {
+            vars: vec![],
+            names: HashMap::new(),
+            taken,
+        }
+    }
+
+    fn get(&mut self, term: &Term) -> Type {
+        match term {
+            Term::Var(v) => {
+                if !self.names.contains_key(v) {
+                    let name = (0..)
+                        .map(|i| {
+                            let letter = (b'a' + (i % 26) as u8) as char;
+                            match i / 26 {
+                                0 => letter.to_string(),
+                                n => format!("{}{}", letter, n),
+                            }
+                        })
+                        .find(|name| !self.taken.contains(name))
+                        .unwrap();
+                    self.taken.insert(name.clone());
+                    self.vars.push(name.clone());
+                    self.names.insert(*v, name);
+                }
+                Type::Base(self.names[v].clone())
+            }
+            Term::Base(name) => Type::Base(name.clone()),
+            Term::Arrow(t1, t2) => Type::Arrow(Box::new(self.get(t1)), Box::new(self.get(t2))),
+        }
+    }
+}
+
+impl fmt::Debug for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            write!(f, "{}", self.1)
+        } else {
+            write!(f, "∀{}. {}", self.0.join(" "), self.1)
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{ExpressionParser, ProgramParser, TypeParser};
+    use pretty_assertions::assert_eq;
+
+    fn ty(source: &str) -> Type {
+        TypeParser::new().parse(source).unwrap()
+    }
+
+    fn infer(source: &str) -> Result<String, TypeError> {
+        let expression = ProgramParser::new().parse(source).unwrap();
+        expression.infer(&Context::new()).map(|s| s.to_string())
+    }
+
+    #[test]
+    fn combinators() {
+        assert_eq!(Ok("∀a. a → a".into()), infer(r"\x.x"));
+        assert_eq!(Ok("∀a b. a → b → a".into()), infer(r"\x y.x"));
+        assert_eq!(
+            Ok("∀a b c. (a → b → c) → (a → b) → a → c".into()),
+            infer(r"\x y z.x z (y z)")
+        );
+        assert_eq!(
+            Ok("∀a b c. (a → b) → (c → a) → c → b".into()),
+            infer(r"\f g x.f (g x)")
+        );
+        assert_eq!(
+            Ok("∀a b c. (a → b → c) → b → a → c".into()),
+            infer(r"\f x y.f y x")
+        );
+        assert_eq!(Ok("∀a. (a → a) → a → a".into()), infer(r"\f x.f (f x)"));
+    }
+
+    #[test]
+    fn let_polymorphism() {
+        assert_eq!(Ok("∀a. a → a".into()), infer(r"let id = \x.x in id id"));
+        assert_eq!(
+            Ok("∀a b. a → b → b".into()),
+            infer(r"k = \x y.x; id = \x.x; k id")
+        );
+
+        // Every redex is a `let`, but variables bound only by an abstraction
+        // are monomorphic.
+        assert!(infer(r"(\g.(\f.f f) g) (\x.x)").is_ok());
+        assert!(infer(r"\f.f f").is_err());
+        assert!(infer(r"\i.(\f.f f) i").is_err());
+    }
+
+    #[test]
+    fn annotations() {
+        assert_eq!(Ok("a → a".into()), infer(r"\x:a.x"));
+        assert_eq!(Ok("∀b. a → b → a".into()), infer(r"\x:a y.x"));
+        assert_eq!(Ok("∀b. (a → b) → b".into()), infer(r"\f.f x:a"));
+
+        let mut context = Context::new();
+        context.insert("zero".into(), ty("n"));
+        context.insert("succ".into(), ty("n → n"));
+        let e = ExpressionParser::new().parse(r"\f.succ (f zero)").unwrap();
+        assert_eq!(Ok(Scheme(vec![], ty("(n → n) → n"))), e.infer(&context));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Err(TypeError::Occurs {
+                expression: γ!(x, x),
+                variable: ty("a"),
+                ty: ty("a → b"),
+            }),
+            infer(r"\x.x x")
+        );
+        assert_eq!(Err(TypeError::Unbound(variable!(y))), infer(r"\x.y"));
+        assert_eq!(
+            Err(TypeError::NotAFunction {
+                expression: Expression::Var(variable!(x, a)),
+                found: ty("a"),
+            }),
+            infer(r"x:a y")
+        );
+        assert_eq!(
+            Err(TypeError::Mismatch {
+                expression: Expression::Var(variable!(y, b)),
+                expected: ty("a"),
+                found: ty("b"),
+            }),
+            infer(r"\f:a→a.f y:b")
+        );
+        assert!(infer(r"(\x.x x) (\x.x x)").is_err());
+    }
+}
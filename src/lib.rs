@@ -1,12 +1,31 @@
 //! This project started as just the parse.lalrpop and AST, but grew into a bit
 //! more.
 //!
-//! Evaluation of λ-expressions is _currently_ done in a single big-step
-//! semantics [`Expression::normalize`] function. The reduction strategy is
-//! so far only configurable by η.
+//! Evaluation of λ-expressions is done either with the big-step semantics
+//! [`Expression::normalize`] function, or one step at a time with
+//! [`Expression::apply`] and [`Expression::reductions`]. Both are configured
+//! by a [`Strategy`], and [`Expression::normalize_with`] also takes your own
+//! [`ReductionStrategy`].
 //!
-//! See the `impl From` items under [`Expression`]. These define conversions
-//! between Rust and λ-expressions. These are all defined in `mod encode`.
+//! Variables may be annotated with a [`Type`], as in `λx:a→a.x`, and
+//! [`Expression::typecheck`] checks these annotations in the simply typed
+//! λ-calculus. Without annotations, [`Expression::infer`] finds the most
+//! general [`Scheme`] of a term.
+//!
+//! The standard combinators and operations on encoded data are defined by
+//! name in the [`Prelude`], which terms can be [`Expression::resolve`]d in.
+//! Terms can also be translated to [`Combinator`]s of combinatory logic, and
+//! reduced there.
+//!
+//! The [`Krivine`] and [`Cek`] machines evaluate terms with environments of
+//! closures instead of substitution, one inspectable transition at a time,
+//! and [`Expression::nbe`] normalizes by evaluating terms into Rust closures.
+//! To choose each step by hand instead, [`Expression::redexes`] lists every
+//! redex by its path, which [`Expression::reduce_at`] contracts.
+//!
+//! See the `impl From` and `impl TryFrom` items under [`Expression`]. These
+//! define conversions between Rust and λ-expressions. These are all defined in
+//! `mod encode`.
 //!
 //! ```
 //! #![feature(box_syntax)]
@@ -50,7 +69,7 @@ extern crate wasm_bindgen;
 #[cfg(test)]
 extern crate pretty_assertions;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::fmt;
 
 #[cfg(feature = "wasm")]
@@ -58,7 +77,12 @@ pub mod wasm;
 
 // TODO: Polish and test.
 mod normal;
-pub use self::normal::Strategy;
+pub use self::normal::{
+    Exhausted, Limit, Limits, ReductionStrategy, Reductions, Stats, Strategy,
+};
+
+// Call-by-need evaluation, by graph reduction with shared thunks.
+mod graph;
 
 // The wonderful and easy to use `λ` and `abs!` macros.
 //
@@ -67,8 +91,55 @@ pub use self::normal::Strategy;
 #[macro_use]
 mod macros;
 
+// Equality of expressions up to the names of bound variables.
+mod alpha;
+pub use self::alpha::Alpha;
+
+// A nameless representation of expressions.
+mod debruijn;
+pub use self::debruijn::DeBruijn;
+
+// Source locations of parsed expressions, and parse error reporting.
+mod span;
+pub use self::span::{Span, Spans};
+mod diagnostic;
+pub use self::diagnostic::Diagnostic;
+
+// Displaying expressions with minimal parentheses.
+mod pretty;
+pub use self::pretty::{Pretty, Style};
+
+// Simple types, type checking, and type inference.
+mod types;
+pub use self::types::{Context, Type, TypeError};
+mod infer;
+pub use self::infer::Scheme;
+
 // Church encoded λ-calculus data types, and conversions to Rust data types
 mod encode;
+pub use self::encode::{Binary, Byte, DecodeError, Signed};
+
+// Named combinators and operations, for use with `Expression::resolve`.
+mod prelude;
+pub use self::prelude::Prelude;
+
+// Replacing names with their definitions from an environment.
+mod resolve;
+
+// Combinatory logic, translated to and from λ-expressions.
+mod combinator;
+pub use self::combinator::{Basis, Combinator};
+
+// The Krivine and CEK machines, evaluating closures in environments.
+mod machine;
+pub use self::machine::{Cek, Closure, Control, Env, Frame, Krivine, Value};
+
+// Normalization by evaluation, into Rust closures and back.
+mod nbe;
+
+// Finding redexes by their path, and contracting them one at a time.
+mod redex;
+pub use self::redex::{Move, Redex};
 
 /// A mutually recursive definition for all lambda expressions
 ///
@@ -92,7 +163,7 @@ pub enum Expression {
 /// assert!(parser.parse("x").is_ok());
 /// ```
 #[derive(Clone, Hash, PartialEq, Eq)]
-pub struct Variable(pub String, pub Option<String>);
+pub struct Variable(pub String, pub Option<Type>);
 
 /// An abstraction over a bound variable
 ///
@@ -116,9 +187,42 @@ pub struct Application(pub Box<Expression>, pub Box<Expression>);
 
 impl Expression {
     /// α-conversion
+    ///
+    /// Every abstraction binding `old` is changed to bind `new` instead. An
+    /// abstraction whose body has `new` free is left as is, since renaming it
+    /// would capture `new`.
+    ///
+    /// ```
+    /// use lalrpop_lambda::Variable;
+    ///
+    /// let parser = lalrpop_lambda::parse::ExpressionParser::new();
+    /// let x = Variable("x".into(), None);
+    /// let y = Variable("y".into(), None);
+    ///
+    /// let expression = parser.parse("λx.x z").unwrap();
+    /// assert_eq!(parser.parse("λy.y z").unwrap(), expression.rename(&x, &y));
+    ///
+    /// let expression = parser.parse("λx.x y").unwrap();
+    /// assert_eq!(expression, expression.rename(&x, &y));
+    /// ```
     pub fn rename(&self, old: &Variable, new: &Variable) -> Self {
-        dbg!(old, new);
-        unimplemented!()
+        match self {
+            Expression::Var(_) => self.clone(),
+            Expression::Abs(Abstraction(id, body)) => {
+                let body = body.rename(old, new);
+                if id.0 == old.0 && !body.free_variables().iter().any(|v| v.0 == new.0) {
+                    let body = body.substitute(&Expression::Var(Variable(new.0.clone(), None)), id);
+                    let id = Variable(new.0.clone(), id.1.clone());
+                    Expression::Abs(Abstraction(id, Box::new(body)))
+                } else {
+                    Expression::Abs(Abstraction(id.clone(), Box::new(body)))
+                }
+            }
+            Expression::App(Application(e1, e2)) => Expression::App(Application(
+                Box::new(e1.rename(old, new)),
+                Box::new(e2.rename(old, new)),
+            )),
+        }
     }
 
     pub fn variables(&self) -> HashSet<Variable> {
@@ -135,6 +239,38 @@ impl Expression {
         }
     }
 
+    /// The number of variables, abstractions and applications in M
+    ///
+    /// ```
+    /// let parser = lalrpop_lambda::parse::ExpressionParser::new();
+    ///
+    /// assert_eq!(1, parser.parse("x").unwrap().size());
+    /// assert_eq!(6, parser.parse("λx.(x y) z").unwrap().size());
+    /// ```
+    pub fn size(&self) -> usize {
+        match self {
+            Expression::Var(_) => 1,
+            Expression::Abs(Abstraction(_, body)) => 1 + body.size(),
+            Expression::App(Application(e1, e2)) => 1 + e1.size() + e2.size(),
+        }
+    }
+
+    /// The length of the longest path from M to one of its variables
+    ///
+    /// ```
+    /// let parser = lalrpop_lambda::parse::ExpressionParser::new();
+    ///
+    /// assert_eq!(0, parser.parse("x").unwrap().depth());
+    /// assert_eq!(3, parser.parse("λx.(x y) z").unwrap().depth());
+    /// ```
+    pub fn depth(&self) -> usize {
+        match self {
+            Expression::Var(_) => 0,
+            Expression::Abs(Abstraction(_, body)) => 1 + body.depth(),
+            Expression::App(Application(e1, e2)) => 1 + e1.depth().max(e2.depth()),
+        }
+    }
+
     /// FV(M) is the set of variables in M, not closed by a λ term.
     ///
     /// ```
@@ -168,48 +304,6 @@ impl Expression {
                 .collect(),
         }
     }
-
-    /// ```
-    /// # #![feature(box_syntax)]
-    /// # #[macro_use]
-    /// # extern crate lalrpop_lambda;
-    /// use std::collections::HashMap;
-    ///
-    /// # fn main() {
-    /// let mut env = HashMap::new();
-    /// env.insert(variable!(id), abs!{x.x});
-    /// env.insert(variable!(ad), abs!{x.y});
-    /// env.insert(variable!(x), 1.into());
-    ///
-    /// assert_eq!(var!(q), var!(q).resolve(&env));
-    /// assert_eq!(1u64, var!(x).resolve(&env).into());
-    ///
-    /// // Works with functions too!
-    /// let id: fn(u64) -> u64 = var!(id).resolve(&env).into();
-    /// assert_eq!(1, id(1));
-    /// let ad: fn(u64) -> u64 = var!(ad).resolve(&env).into();
-    /// assert_eq!(u64::from(var!(y)), ad(0));
-    /// assert_eq!(u64::from(var!(y)), ad(1));
-    /// # }
-    /// ```
-    pub fn resolve(&self, env: &HashMap<Variable, Expression>) -> Expression {
-        match self {
-            Expression::Var(id) => {
-                if let Some(e) = env.get(id) {
-                    e.clone()
-                } else {
-                    self.clone()
-                }
-            }
-            Expression::Abs(Abstraction(id, box body)) => {
-                // TODO: Check FV
-                Expression::Abs(Abstraction(id.clone(), Box::new(body.resolve(env))))
-            }
-            Expression::App(Application(box e1, box e2)) => {
-                app!({ e1.resolve(env) }, { e2.resolve(env) })
-            }
-        }
-    }
 }
 
 impl Expression {
@@ -255,17 +349,13 @@ impl fmt::Debug for Expression {
 
 impl fmt::Debug for Variable {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if let Some(ty) = &self.1 {
-            write!(f, "{}:{}", self.0, ty)
-        } else {
-            write!(f, "{}", self.0)
-        }
+        write!(f, "{}", self.pretty(Style::default()))
     }
 }
 
 impl fmt::Display for Expression {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "{}", self.pretty(Style::default()))
     }
 }
 
@@ -283,7 +373,7 @@ lalrpop_mod! {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parse::ExpressionParser;
+    use crate::parse::{ExpressionParser, ProgramParser};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -307,8 +397,84 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
-    fn rename() {}
+    fn let_() {
+        let parser = ExpressionParser::new();
+
+        assert_eq!(
+            parser.parse(r"(λx.x) y").unwrap(),
+            parser.parse(r"let x = y in x").unwrap()
+        );
+        assert_eq!(
+            parser.parse(r"(λid.id id) (λx.x)").unwrap(),
+            parser.parse(r"let id = λx.x in id id").unwrap()
+        );
+        assert_eq!(
+            parser.parse(r"(λa.(λb.a b) (a c)) (λx.x)").unwrap(),
+            parser.parse(r"let a = λx.x in let b = a c in a b").unwrap()
+        );
+        assert_eq!(
+            parser.parse(r"(λx:t.x) y").unwrap(),
+            parser.parse(r"let x:t = y in x").unwrap()
+        );
+        assert!(parser.parse(r"let x = y").is_err());
+        assert!(parser.parse(r"let x in y").is_err());
+    }
+
+    #[test]
+    fn program() {
+        let parser = ProgramParser::new();
+
+        assert_eq!(
+            ExpressionParser::new().parse(r"x y").unwrap(),
+            parser.parse(r"x y").unwrap()
+        );
+        assert_eq!(
+            ExpressionParser::new()
+                .parse(r"let id = λx.x in let k = λx y.x in k id")
+                .unwrap(),
+            parser
+                .parse(
+                    r"
+                    id = λx.x;
+                    k = λx y.x;
+                    k id
+                    "
+                )
+                .unwrap()
+        );
+        assert!(parser.parse(r"id = λx.x;").is_err());
+        assert!(parser.parse(r"id = λx.x id").is_err());
+    }
+
+    #[test]
+    fn rename() {
+        let parser = ExpressionParser::new();
+        let (x, y) = (variable!(x), variable!(y));
+
+        assert_eq!(var!(x), var!(x).rename(&x, &y));
+        assert_eq!(abs! {y.y}, abs! {x.x}.rename(&x, &y));
+        assert_eq!(abs! {z.z}, abs! {z.z}.rename(&x, &y));
+        assert_eq!(
+            parser.parse(r"x (λy.y) x").unwrap(),
+            parser.parse(r"x (λx.x) x").unwrap().rename(&x, &y)
+        );
+        assert_eq!(
+            Expression::Abs(Abstraction(
+                variable!(y),
+                Box::new(Expression::Abs(Abstraction(
+                    variable!("y'"),
+                    Box::new(app!(y, var!("y'"))),
+                ))),
+            )),
+            parser.parse(r"λx.λy.x y").unwrap().rename(&x, &y)
+        );
+
+        // Capturing `y` isn't allowed.
+        let expression = parser.parse(r"λx.x y").unwrap();
+        assert_eq!(expression, expression.rename(&x, &y));
+        // Type annotations stay with the binder.
+        assert_eq!(abs! {y:t.y}, abs! {x:t.x}.rename(&x, &y));
+    }
 
     #[test]
     #[ignore]
@@ -335,16 +501,4 @@ mod tests {
                 .free_variables()
         );
     }
-
-    #[test]
-    fn resolve() {
-        let env = map! {
-            variable!(n) => 1.into(),
-        };
-
-        assert_eq!(var!(q), var!(q).resolve(&env));
-        assert_eq!(1u64, var!(n).resolve(&env).into());
-
-        // TODO: Add more, starting with examples/env.rs.
-    }
 }
@@ -0,0 +1,465 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::{Abstraction, Application, Expression, Limit, Variable};
+
+/// The values bound to each variable in scope, innermost first
+///
+/// Environments are persistent, so extending one shares it with every
+/// closure which captured it.
+pub struct Env<'a, T>(Option<Rc<Binding<'a, T>>>);
+
+struct Binding<'a, T> {
+    id: &'a Variable,
+    value: T,
+    next: Env<'a, T>,
+}
+
+impl<'a, T> Env<'a, T> {
+    /// The environment without any bindings
+    pub fn new() -> Self {
+        Env(None)
+    }
+
+    /// Extend the environment, shadowing any binding of the same name
+    pub fn bind(&self, id: &'a Variable, value: T) -> Self {
+        Env(Some(Rc::new(Binding {
+            id,
+            value,
+            next: self.clone(),
+        })))
+    }
+
+    /// The innermost binding of a name
+    pub fn get(&self, id: &str) -> Option<&T> {
+        self.iter().find(|(v, _)| v.0 == id).map(|(_, value)| value)
+    }
+
+    /// Every binding, innermost first, including shadowed ones
+    pub fn iter(&self) -> impl Iterator<Item = (&'a Variable, &T)> {
+        let mut env = self;
+        std::iter::from_fn(move || match env {
+            Env(Some(binding)) => {
+                env = &binding.next;
+                Some((binding.id, &binding.value))
+            }
+            Env(None) => None,
+        })
+    }
+
+    // Read back a term in this environment, by substituting the read back
+    // values of its free variables.
+    fn read<F>(&self, e: &Expression, read: F) -> Expression
+    where
+        F: Fn(&T) -> Expression,
+    {
+        let env: HashMap<_, _> = e
+            .free_variables()
+            .into_iter()
+            .filter_map(|id| self.get(&id.0).map(|value| (id, read(value))))
+            .collect();
+        e.resolve(&env)
+    }
+}
+
+// Deriving these would require `T: Clone` and `T: Debug`.
+impl<'a, T> Clone for Env<'a, T> {
+    fn clone(&self) -> Self {
+        Env(self.0.clone())
+    }
+}
+
+impl<'a, T> Default for Env<'a, T> {
+    fn default() -> Self {
+        Env::new()
+    }
+}
+
+// Long environments would otherwise be dropped recursively.
+impl<'a, T> Drop for Env<'a, T> {
+    fn drop(&mut self) {
+        let mut env = self.0.take();
+        while let Some(binding) = env {
+            env = match Rc::try_unwrap(binding) {
+                Ok(mut binding) => binding.next.0.take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for Env<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A term paired with the environment of its free variables
+#[derive(Clone, Debug)]
+pub struct Closure<'a> {
+    pub term: &'a Expression,
+    pub env: Env<'a, Closure<'a>>,
+}
+
+impl<'a> Closure<'a> {
+    /// The term with its environment substituted into it
+    pub fn expression(&self) -> Expression {
+        self.env.read(self.term, Closure::expression)
+    }
+}
+
+/// The Krivine machine, which evaluates to weak head normal form by name
+///
+/// Arguments are pushed on the stack as closures without being evaluated, and
+/// popped by the abstraction they're applied to. This agrees with
+/// [`Strategy::CallByName`](crate::Strategy::CallByName), without rebuilding
+/// the term on each β-reduction.
+///
+/// ```
+/// use lalrpop_lambda::Krivine;
+/// use lalrpop_lambda::parse::ExpressionParser;
+///
+/// let parser = ExpressionParser::new();
+/// let e = parser.parse("(λx.λy.x) a ((λx.x x) (λx.x x))").unwrap();
+///
+/// let mut machine = Krivine::new(&e);
+/// assert!(machine.step() && machine.step());
+/// assert_eq!(2, machine.stack.len());
+///
+/// machine.run();
+/// assert_eq!(parser.parse("a").unwrap(), machine.expression());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Krivine<'a> {
+    /// The closure being evaluated
+    pub closure: Closure<'a>,
+    /// The arguments it's applied to, the next to be applied last
+    pub stack: Vec<Closure<'a>>,
+}
+
+impl<'a> Krivine<'a> {
+    /// The initial state, evaluating a term in the empty environment
+    pub fn new(e: &'a Expression) -> Self {
+        Krivine {
+            closure: Closure {
+                term: e,
+                env: Env::new(),
+            },
+            stack: vec![],
+        }
+    }
+
+    /// Make a single transition, returning `false` when the machine has
+    /// halted
+    pub fn step(&mut self) -> bool {
+        match self.closure.term {
+            Expression::App(Application(e1, e2)) => {
+                self.stack.push(Closure {
+                    term: e2,
+                    env: self.closure.env.clone(),
+                });
+                self.closure.term = e1;
+            }
+            Expression::Abs(Abstraction(id, body)) => match self.stack.pop() {
+                Some(argument) => {
+                    self.closure.env = self.closure.env.bind(id, argument);
+                    self.closure.term = body;
+                }
+                None => return false,
+            },
+            Expression::Var(id) => match self.closure.env.get(&id.0) {
+                Some(closure) => self.closure = closure.clone(),
+                None => return false,
+            },
+        }
+        true
+    }
+
+    /// Step until the machine halts
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    /// Step until the machine halts, or `fuel` transitions have been made
+    pub fn run_with_limit(&mut self, fuel: usize) -> Result<(), Limit> {
+        run_with_limit(fuel, || self.step())
+    }
+
+    /// Read the state back as a term, applying the current closure to the
+    /// stack
+    pub fn expression(&self) -> Expression {
+        self.stack
+            .iter()
+            .rev()
+            .fold(self.closure.expression(), |e, argument| {
+                Expression::App(Application(Box::new(e), Box::new(argument.expression())))
+            })
+    }
+}
+
+/// A weak normal form reached by the [`Cek`] machine
+#[derive(Clone, Debug)]
+pub enum Value<'a> {
+    Closure(&'a Variable, &'a Expression, Env<'a, Value<'a>>),
+    /// A variable without a binding, applied to values
+    Neutral(&'a Variable, Vec<Value<'a>>),
+}
+
+impl<'a> Value<'a> {
+    /// Read the value back as a term
+    pub fn expression(&self) -> Expression {
+        match self {
+            Value::Closure(id, body, env) => {
+                let abs = Expression::Abs(Abstraction((*id).clone(), Box::new((*body).clone())));
+                env.read(&abs, Value::expression)
+            }
+            Value::Neutral(id, arguments) => {
+                arguments
+                    .iter()
+                    .fold(Expression::Var((*id).clone()), |e, argument| {
+                        Expression::App(Application(Box::new(e), Box::new(argument.expression())))
+                    })
+            }
+        }
+    }
+}
+
+/// What the [`Cek`] machine is doing
+#[derive(Clone, Debug)]
+pub enum Control<'a> {
+    /// Evaluating a term in an environment
+    Eval(&'a Expression, Env<'a, Value<'a>>),
+    /// Returning a value to the continuation
+    Return(Value<'a>),
+}
+
+/// A frame of the [`Cek`] machine's continuation
+#[derive(Clone, Debug)]
+pub enum Frame<'a> {
+    /// Evaluate this argument once the operator is a value
+    Argument(&'a Expression, Env<'a, Value<'a>>),
+    /// Apply this operator once the argument is a value
+    Function(Value<'a>),
+}
+
+/// The CEK machine, which evaluates to weak normal form by value
+///
+/// The operator of an application is evaluated first, then its argument, and
+/// then the body of the abstraction with the argument's value bound. This
+/// agrees with [`Strategy::CallByValue`](crate::Strategy::CallByValue).
+///
+/// ```
+/// use lalrpop_lambda::{Cek, Control};
+/// use lalrpop_lambda::parse::ExpressionParser;
+///
+/// let parser = ExpressionParser::new();
+/// let e = parser.parse("(λx.x x) ((λy.y) z)").unwrap();
+///
+/// let mut machine = Cek::new(&e);
+/// machine.run();
+/// assert!(machine.continuation.is_empty());
+/// if let Control::Return(value) = &machine.control {
+///     assert_eq!(parser.parse("z z").unwrap(), value.expression());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cek<'a> {
+    pub control: Control<'a>,
+    /// The frames to return to, the innermost last
+    pub continuation: Vec<Frame<'a>>,
+}
+
+impl<'a> Cek<'a> {
+    /// The initial state, evaluating a term in the empty environment
+    pub fn new(e: &'a Expression) -> Self {
+        Cek {
+            control: Control::Eval(e, Env::new()),
+            continuation: vec![],
+        }
+    }
+
+    /// Make a single transition, returning `false` when the machine has
+    /// halted
+    pub fn step(&mut self) -> bool {
+        let control = match &self.control {
+            Control::Eval(Expression::App(Application(e1, e2)), env) => {
+                self.continuation.push(Frame::Argument(e2, env.clone()));
+                Control::Eval(e1, env.clone())
+            }
+            Control::Eval(Expression::Abs(Abstraction(id, body)), env) => {
+                Control::Return(Value::Closure(id, body, env.clone()))
+            }
+            Control::Eval(Expression::Var(id), env) => match env.get(&id.0) {
+                Some(value) => Control::Return(value.clone()),
+                None => Control::Return(Value::Neutral(id, vec![])),
+            },
+            Control::Return(value) => match self.continuation.pop() {
+                Some(Frame::Argument(e, env)) => {
+                    self.continuation.push(Frame::Function(value.clone()));
+                    Control::Eval(e, env)
+                }
+                Some(Frame::Function(Value::Closure(id, body, env))) => {
+                    Control::Eval(body, env.bind(id, value.clone()))
+                }
+                Some(Frame::Function(Value::Neutral(id, mut arguments))) => {
+                    arguments.push(value.clone());
+                    Control::Return(Value::Neutral(id, arguments))
+                }
+                None => return false,
+            },
+        };
+        self.control = control;
+        true
+    }
+
+    /// Step until the machine halts
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    /// Step until the machine halts, or `fuel` transitions have been made
+    pub fn run_with_limit(&mut self, fuel: usize) -> Result<(), Limit> {
+        run_with_limit(fuel, || self.step())
+    }
+
+    /// Read the state back as a term, plugging the control into the
+    /// continuation
+    pub fn expression(&self) -> Expression {
+        let mut e = match &self.control {
+            Control::Eval(e, env) => env.read(e, Value::expression),
+            Control::Return(value) => value.expression(),
+        };
+        for frame in self.continuation.iter().rev() {
+            e = match frame {
+                Frame::Argument(argument, env) => {
+                    let argument = env.read(argument, Value::expression);
+                    Expression::App(Application(Box::new(e), Box::new(argument)))
+                }
+                Frame::Function(function) => {
+                    Expression::App(Application(Box::new(function.expression()), Box::new(e)))
+                }
+            };
+        }
+        e
+    }
+}
+
+fn run_with_limit<F>(fuel: usize, mut step: F) -> Result<(), Limit>
+where
+    F: FnMut() -> bool,
+{
+    for _ in 0..fuel {
+        if !step() {
+            return Ok(());
+        }
+    }
+    if step() {
+        Err(Limit::Steps(fuel))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::ExpressionParser;
+    use crate::Strategy;
+
+    const TERMS: [&str; 8] = [
+        r"(λx.y) ((λx.x x) (λx.x x))",
+        r"λx.(λy.y) x",
+        r"x ((λy.y) z)",
+        r"(λx.x) (λy.(λz.z) y)",
+        r"(λx.λy.x y) a b",
+        r"(λx.λy.x) y",
+        r"(λf.λx.f (f x)) (λf.λx.f (f x)) g",
+        r"(λn.λf.λx.f (n f x)) ((λn.λf.λx.f (n f x)) (λf.λx.x))",
+    ];
+
+    #[test]
+    fn krivine() {
+        let parser = ExpressionParser::new();
+        for source in &TERMS {
+            let e = parser.parse(source).unwrap();
+            let mut machine = Krivine::new(&e);
+            machine.run_with_limit(1000).unwrap();
+            let normal = e.normalize(&Strategy::CallByName);
+            assert!(machine.expression().alpha_eq(&normal), "{}", source);
+        }
+    }
+
+    #[test]
+    fn cek() {
+        let parser = ExpressionParser::new();
+        for source in &TERMS[1..] {
+            let e = parser.parse(source).unwrap();
+            let mut machine = Cek::new(&e);
+            machine.run_with_limit(1000).unwrap();
+            let normal = e.normalize(&Strategy::CallByValue);
+            assert!(machine.expression().alpha_eq(&normal), "{}", source);
+        }
+
+        // Arguments are evaluated before they're passed.
+        let e = parser.parse(TERMS[0]).unwrap();
+        let mut machine = Cek::new(&e);
+        assert_eq!(Err(Limit::Steps(1000)), machine.run_with_limit(1000));
+    }
+
+    #[test]
+    fn read_back() {
+        let parser = ExpressionParser::new();
+        let e = parser.parse(r"(λx.λy.x y) (λz.y) b").unwrap();
+
+        // Every intermediate state reads back to a reduct of the term.
+        let mut machine = Krivine::new(&e);
+        let mut states = vec![machine.expression()];
+        while machine.step() {
+            states.push(machine.expression());
+        }
+        let normal = e.normalize(&Strategy::CallByName);
+        for state in &states {
+            assert!(state.normalize(&Strategy::CallByName).alpha_eq(&normal));
+        }
+        assert_eq!(e, states[0]);
+        assert_eq!("y", normal.to_string());
+
+        let mut machine = Cek::new(&e);
+        while machine.step() {
+            let state = machine.expression();
+            assert!(state.normalize(&Strategy::CallByValue).alpha_eq(&normal));
+        }
+    }
+
+    #[test]
+    fn capture() {
+        let parser = ExpressionParser::new();
+        let e = parser.parse(r"(λx.λy.x) y").unwrap();
+        let mut machine = Krivine::new(&e);
+        machine.run();
+        assert_eq!("λy'.y", machine.expression().to_string());
+        assert_eq!(1, machine.closure.env.iter().count());
+        assert!(machine.closure.env.get("x").is_some());
+    }
+
+    #[test]
+    fn long() {
+        // Thousands of β-reductions, each without rebuilding the term.
+        let n = Expression::from(1000u64);
+        let e = app!(
+            app!({ &n }, {
+                abs! {y.y}
+            }),
+            a
+        );
+
+        let mut krivine = Krivine::new(&e);
+        krivine.run();
+        assert_eq!(var!(a), krivine.expression());
+
+        let mut cek = Cek::new(&e);
+        cek.run();
+        assert_eq!(var!(a), cek.expression());
+    }
+}
@@ -16,10 +16,13 @@ macro_rules! variable {
         $crate::Variable($b.into(), None)
     }};
     ($b:ident, $ty:ident) => {{
-        $crate::Variable(stringify!($b).into(), Some(stringify!($ty).into()))
+        $crate::Variable(
+            stringify!($b).into(),
+            Some($crate::Type::Base(stringify!($ty).into())),
+        )
     }};
     ($b:expr, $ty:ident) => {{
-        $crate::Variable($b.into(), Some(stringify!($ty).into()))
+        $crate::Variable($b.into(), Some($crate::Type::Base(stringify!($ty).into())))
     }};
 }
 
@@ -115,13 +118,13 @@ macro_rules! λ {
         abs!($body)
     };
     {$($arg:ident)* : $ty:ident . $body:ident} => {
-        abs!($($arg)* . $body)
+        abs!($($arg : $ty)* . $body)
     };
     {$($arg:ident)* . $body:ident} => {
         abs!($($arg)* . $body)
     };
     {$($arg:ident)* : $ty:ident . $body:expr} => {{
-        abs!($($arg)* . $body)
+        abs!($($arg : $ty)* . $body)
     }};
     {$($arg:ident)* . $body:expr} => {{
         abs!($($arg)* . $body)
@@ -0,0 +1,293 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use crate::{Abstraction, Application, Context, Env, Expression, Type, Variable};
+
+/// The meaning of a term, either a function on meanings or stuck on a
+/// variable
+#[derive(Clone)]
+enum Value<'a> {
+    Lam(Cow<'a, Variable>, Rc<dyn Fn(Thunk<'a>) -> Value<'a> + 'a>),
+    Neutral(Rc<Neutral<'a>>),
+}
+
+enum Neutral<'a> {
+    Var(Rc<str>),
+    /// An application, with the type of the argument when it's known
+    App(Rc<Neutral<'a>>, Thunk<'a>, Option<&'a Type>),
+}
+
+/// An argument, evaluated at most once when its meaning is needed
+type Thunk<'a> = Rc<RefCell<State<'a>>>;
+
+enum State<'a> {
+    Delayed(&'a Expression, Env<'a, Thunk<'a>>),
+    Forced(Value<'a>),
+}
+
+fn forced(value: Value) -> Thunk {
+    Rc::new(RefCell::new(State::Forced(value)))
+}
+
+fn variable<'a>(name: &str) -> Rc<Neutral<'a>> {
+    Rc::new(Neutral::Var(Rc::from(name)))
+}
+
+fn force<'a>(thunk: &Thunk<'a>, context: Option<&'a Context>) -> Value<'a> {
+    let (e, env) = match &*thunk.borrow() {
+        State::Forced(value) => return value.clone(),
+        State::Delayed(e, env) => (*e, env.clone()),
+    };
+    let value = eval(e, &env, context);
+    *thunk.borrow_mut() = State::Forced(value.clone());
+    value
+}
+
+fn eval<'a>(
+    e: &'a Expression,
+    env: &Env<'a, Thunk<'a>>,
+    context: Option<&'a Context>,
+) -> Value<'a> {
+    match e {
+        Expression::Var(id) => match env.get(&id.0) {
+            Some(thunk) => force(thunk, context),
+            None => {
+                let ty = context.and_then(|context| context.get(&id.0));
+                reflect(ty, variable(&id.0))
+            }
+        },
+        Expression::Abs(Abstraction(id, body)) => {
+            let env = env.clone();
+            let f = move |argument| eval(body, &env.bind(id, argument), context);
+            Value::Lam(Cow::Borrowed(id), Rc::new(f))
+        }
+        Expression::App(Application(e1, e2)) => {
+            let argument = Rc::new(RefCell::new(State::Delayed(e2, env.clone())));
+            apply(eval(e1, env, context), argument)
+        }
+    }
+}
+
+fn apply<'a>(f: Value<'a>, argument: Thunk<'a>) -> Value<'a> {
+    match f {
+        Value::Lam(_, f) => f(argument),
+        Value::Neutral(n) => Value::Neutral(Rc::new(Neutral::App(n, argument, None))),
+    }
+}
+
+// The meaning of a neutral term at a type, η-expanded into functions when
+// the type is an arrow.
+fn reflect<'a>(ty: Option<&'a Type>, n: Rc<Neutral<'a>>) -> Value<'a> {
+    match ty {
+        Some(Type::Arrow(a, b)) => {
+            let f = move |argument| {
+                let n = Rc::new(Neutral::App(n.clone(), argument, Some(&**a)));
+                reflect(Some(&**b), n)
+            };
+            let id = Variable("x".into(), Some((**a).clone()));
+            Value::Lam(Cow::Owned(id), Rc::new(f))
+        }
+        _ => Value::Neutral(n),
+    }
+}
+
+/// Reading meanings back as terms in normal form
+struct Reify<'a> {
+    context: Option<&'a Context>,
+    /// The names of the variables in scope, which new binders must avoid
+    scope: HashSet<String>,
+}
+
+impl<'a> Reify<'a> {
+    fn value(&mut self, ty: Option<&'a Type>, value: Value<'a>) -> Expression {
+        match (ty, value) {
+            (Some(Type::Arrow(a, b)), value) => {
+                let name = match &value {
+                    Value::Lam(id, _) => self.bind(&id.0),
+                    Value::Neutral(_) => self.bind("x"),
+                };
+                let argument = reflect(Some(a), variable(&name));
+                let body = self.value(Some(b), apply(value, forced(argument)));
+                self.scope.remove(&name);
+                Expression::Abs(Abstraction(
+                    Variable(name, Some((**a).clone())),
+                    Box::new(body),
+                ))
+            }
+            (_, Value::Lam(id, f)) => {
+                let name = self.bind(&id.0);
+                let body = self.value(None, f(forced(Value::Neutral(variable(&name)))));
+                self.scope.remove(&name);
+                Expression::Abs(Abstraction(Variable(name, id.1.clone()), Box::new(body)))
+            }
+            (_, Value::Neutral(n)) => self.neutral(&n),
+        }
+    }
+
+    // Bring a variable named apart from those in scope into scope.
+    fn bind(&mut self, name: &str) -> String {
+        let mut name = name.to_string();
+        while self.scope.contains(&name) {
+            name.push('\'');
+        }
+        self.scope.insert(name.clone());
+        name
+    }
+
+    fn neutral(&mut self, n: &Neutral<'a>) -> Expression {
+        match n {
+            Neutral::Var(id) => Expression::Var(Variable(id.to_string(), None)),
+            Neutral::App(n, argument, ty) => {
+                let argument = force(argument, self.context);
+                Expression::App(Application(
+                    Box::new(self.neutral(n)),
+                    Box::new(self.value(*ty, argument)),
+                ))
+            }
+        }
+    }
+}
+
+impl Expression {
+    /// The β-normal form, by normalization by evaluation
+    ///
+    /// The term is evaluated into Rust closures which evaluate the body of an
+    /// abstraction given the meaning of its argument, and the result is read
+    /// back by applying these functions to fresh variables. Arguments are
+    /// evaluated lazily and at most once, so this agrees with
+    /// [`Strategy::Normal`](crate::Strategy::Normal) up to the names of bound
+    /// variables, and like it diverges on terms without a normal form.
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::Prelude;
+    ///
+    /// let prelude = Prelude::new();
+    /// let e = prelude.parse("three = succ (succ (succ zero)); ten = succ (mul three three);
+    ///                        mul ten (mul ten ten)").unwrap();
+    /// assert_eq!(Ok(1000), u64::try_from(e.nbe()));
+    /// ```
+    pub fn nbe(&self) -> Expression {
+        let mut reify = Reify {
+            context: None,
+            scope: self.free_variables().into_iter().map(|v| v.0).collect(),
+        };
+        let value = eval(self, &Env::new(), None);
+        reify.value(None, value)
+    }
+
+    /// The β-normal η-long form at a type, by normalization by evaluation
+    ///
+    /// Every subterm of an arrow type is read back as an abstraction, with its
+    /// variable annotated by the argument type, including the free variables
+    /// given a type in the `context`. The term should have the type `ty`, as
+    /// found by [`Expression::typecheck`] or [`Expression::infer`]; untyped
+    /// parts of it are read back β-normal as in [`Expression::nbe`].
+    ///
+    /// ```
+    /// use lalrpop_lambda::Context;
+    /// use lalrpop_lambda::parse::{ExpressionParser, TypeParser};
+    ///
+    /// let e = ExpressionParser::new().parse("λf.f").unwrap();
+    /// let ty = TypeParser::new().parse("(a → b) → a → b").unwrap();
+    /// assert_eq!("λf:(a → b) x:a.f x", e.nbe_η_long(&ty, &Context::new()).to_string());
+    /// ```
+    pub fn nbe_η_long(&self, ty: &Type, context: &Context) -> Expression {
+        let mut reify = Reify {
+            context: Some(context),
+            scope: self.free_variables().into_iter().map(|v| v.0).collect(),
+        };
+        let value = eval(self, &Env::new(), Some(context));
+        reify.value(Some(ty), value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::parse::{ExpressionParser, TypeParser};
+    use crate::{Prelude, Strategy};
+
+    #[test]
+    fn nbe() {
+        let parser = ExpressionParser::new();
+        for source in &[
+            r"(λx.y) ((λx.x x) (λx.x x))",
+            r"λx.(λy.y) x",
+            r"x ((λy.y) z)",
+            r"(λx.x) (λy.(λz.z) y)",
+            r"(λx.λy.x y) a b",
+            r"(λx.λy.x) y",
+            r"λy.(λx.λy.x y) y",
+            r"(λf.λx.f (f x)) (λf.λx.f (f x))",
+            r"(λn.λf.λx.f (n f x)) ((λn.λf.λx.f (n f x)) (λf.λx.x))",
+        ] {
+            let e = parser.parse(source).unwrap();
+            let normal = e.normalize(&Strategy::Normal(false));
+            assert!(e.nbe().alpha_eq(&normal), "{}", source);
+        }
+    }
+
+    #[test]
+    fn names() {
+        let parser = ExpressionParser::new();
+        let nbe = |source| parser.parse(source).unwrap().nbe().to_string();
+
+        assert_eq!("λx.x", nbe(r"λx.(λy.y) x"));
+        assert_eq!("λy'.y", nbe(r"(λx.λy.x) y"));
+        assert_eq!("λx x'.x'", nbe(r"λx.λx.x"));
+    }
+
+    #[test]
+    fn η_long() {
+        let parser = ExpressionParser::new();
+        let types = TypeParser::new();
+        let η_long = |source, ty, context: &Context| {
+            let ty = types.parse(ty).unwrap();
+            parser
+                .parse(source)
+                .unwrap()
+                .nbe_η_long(&ty, context)
+                .to_string()
+        };
+        let context = Context::new();
+
+        assert_eq!("λx:a.x", η_long(r"λx.x", "a → a", &context));
+        assert_eq!(
+            "λx:(a → a) x':a.x x'",
+            η_long(r"λx.x", "(a → a) → a → a", &context)
+        );
+        assert_eq!(
+            "λf:((a → a) → a) g:(a → a).f (λx:a.g x)",
+            η_long(r"λf g.f g", "((a → a) → a) → (a → a) → a", &context)
+        );
+        assert_eq!("λy:a.y", η_long(r"(λf.f) (λy.y)", "a → a", &context));
+
+        // Free variables are expanded at their types in the context.
+        let mut context = Context::new();
+        context.insert("f".into(), types.parse("(a → a) → a").unwrap());
+        assert_eq!("f (λx:a.x)", η_long(r"f (λx.x)", "a", &context));
+        assert_eq!(
+            "λx:(a → a).f (λx':a.x x')",
+            η_long(r"f", "(a → a) → a", &context)
+        );
+    }
+
+    #[test]
+    fn hundreds() {
+        let prelude = Prelude::new();
+        let e = prelude
+            .parse("three = succ (succ (succ zero)); ten = succ (mul three three); mul (mul ten ten) three")
+            .unwrap();
+        assert_eq!(Ok(300), u64::try_from(e.nbe()));
+
+        let n = Expression::from(400u64);
+        let zero = Expression::from(0u64);
+        let add = &prelude[&variable!(add)];
+        assert_eq!(n, app!(app!({ add }, { &n }), { &zero }).nbe());
+    }
+}
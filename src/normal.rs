@@ -1,6 +1,11 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
 use crate::{Abstraction, Application, Expression, Variable};
 
 /// A reduction strategy for an [`Expression`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Strategy {
     // Innermost reductions
 
@@ -21,6 +26,181 @@ pub enum Strategy {
     HeadSpine(bool),
     // hn: no + he -> normal
     HybridNormal,
+    // nd: no, sharing the reduction of each argument -> normal
+    CallByNeed,
+}
+
+/// Bounds on the work done by [`Expression::normalize_with_limits`]
+///
+/// A limit of `None` is unbounded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum number of small-step reductions, see [`Expression::apply`]
+    pub steps: Option<usize>,
+    /// The maximum size of any intermediate term, see [`Expression::size`]
+    pub size: Option<usize>,
+    /// The maximum depth of any intermediate term, see [`Expression::depth`]
+    pub depth: Option<usize>,
+}
+
+/// The work done by [`Expression::normalize_with_stats`]
+///
+/// Each field counts only the steps which were taken, so a normalization
+/// which runs out of [`Limits`] reports the work done up to that point.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of β-reductions contracted
+    pub beta_steps: usize,
+    /// The number of η-reductions contracted
+    pub eta_steps: usize,
+    /// The number of variables replaced by β-reductions
+    pub substitutions: usize,
+    /// The number of binders α-renamed to avoid capturing a variable
+    pub renames: usize,
+    /// The size of the largest term reached, see [`Expression::size`]
+    pub peak_size: usize,
+    /// The depth of the deepest term reached, see [`Expression::depth`]
+    pub max_depth: usize,
+}
+
+/// The limit which stopped a normalization
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Steps(usize),
+    Size(usize),
+    Depth(usize),
+}
+
+/// A normalization which ran out of [`Limits`] before reaching a normal form
+///
+/// The `expression` is the last term reached within the limits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Exhausted {
+    pub limit: Limit,
+    pub expression: Expression,
+}
+
+impl Stats {
+    fn add(&mut self, other: &Stats) {
+        self.beta_steps += other.beta_steps;
+        self.eta_steps += other.eta_steps;
+        self.substitutions += other.substitutions;
+        self.renames += other.renames;
+    }
+
+    pub(crate) fn reach(&mut self, size: usize, depth: usize) {
+        self.peak_size = self.peak_size.max(size);
+        self.max_depth = self.max_depth.max(depth);
+    }
+}
+
+/// The choices of where to reduce next, made by a [`Strategy`] or your own
+/// evaluation order
+///
+/// A step of [`Expression::apply`] reduces the first of these places which
+/// can be reduced, each with the strategy given for it:
+///
+/// - the body of an abstraction, with the strategy from `body`,
+/// - the operator of an application, with the strategy from `head`,
+/// - the argument of a β-redex, with the strategy from `argument`,
+/// - the β-redex itself, when `beta` is true,
+/// - the operator and then the argument of any other application, with the
+///   strategy from `neutral`.
+///
+/// For example, leftmost innermost reduction of only the redexes under a λ:
+///
+/// ```
+/// use lalrpop_lambda::ReductionStrategy;
+/// use lalrpop_lambda::parse::ExpressionParser;
+///
+/// #[derive(Clone, Copy)]
+/// enum UnderLambda { Outside, Inside }
+///
+/// impl ReductionStrategy for UnderLambda {
+///     fn body(&self) -> Option<Self> { Some(UnderLambda::Inside) }
+///     fn head(&self) -> Self { *self }
+///     fn argument(&self) -> Option<Self> { Some(*self) }
+///     fn neutral(&self) -> Option<Self> { Some(*self) }
+///     fn beta(&self) -> bool { matches!(self, UnderLambda::Inside) }
+/// }
+///
+/// let parser = ExpressionParser::new();
+/// let e = parser.parse("(λx.(λy.y) x) ((λz.z) a)").unwrap();
+/// assert_eq!(parser.parse("(λx.x) ((λz.z) a)").unwrap(),
+///            e.normalize_with(&UnderLambda::Outside));
+/// ```
+pub trait ReductionStrategy: Sized {
+    /// The strategy used to reduce the body of an abstraction, or `None` to
+    /// leave it alone
+    fn body(&self) -> Option<Self>;
+
+    /// The strategy used to reduce the operator of an application, until it
+    /// can no longer be reduced
+    fn head(&self) -> Self;
+
+    /// The strategy used to reduce the operand of a β-redex, before the
+    /// redex itself is contracted
+    fn argument(&self) -> Option<Self> {
+        None
+    }
+
+    /// The strategy used to reduce both sides of an application whose
+    /// operator can't be reduced to an abstraction
+    fn neutral(&self) -> Option<Self>;
+
+    /// Does this strategy contract β-redexes?
+    fn beta(&self) -> bool {
+        true
+    }
+
+    /// Does this strategy η-reduce abstractions `λx.(e x)`?
+    fn eta(&self) -> bool {
+        false
+    }
+}
+
+impl ReductionStrategy for Strategy {
+    fn body(&self) -> Option<Strategy> {
+        match *self {
+            Strategy::CallByName | Strategy::CallByValue => None,
+            _ => Some(*self),
+        }
+    }
+
+    fn head(&self) -> Strategy {
+        match *self {
+            Strategy::CallByName | Strategy::Normal(_) | Strategy::CallByNeed => {
+                Strategy::CallByName
+            }
+            Strategy::CallByValue | Strategy::HybridApplicative => Strategy::CallByValue,
+            Strategy::HeadSpine(η) => Strategy::HeadSpine(η),
+            Strategy::HybridNormal => Strategy::HeadSpine(false),
+            Strategy::Applicative(η) => Strategy::Applicative(η),
+        }
+    }
+
+    fn argument(&self) -> Option<Strategy> {
+        match *self {
+            Strategy::CallByValue => Some(Strategy::CallByValue),
+            Strategy::Applicative(η) => Some(Strategy::Applicative(η)),
+            Strategy::HybridApplicative => Some(Strategy::HybridApplicative),
+            _ => None,
+        }
+    }
+
+    fn neutral(&self) -> Option<Strategy> {
+        match *self {
+            Strategy::CallByName | Strategy::HeadSpine(_) => None,
+            _ => Some(*self),
+        }
+    }
+
+    fn eta(&self) -> bool {
+        match *self {
+            Strategy::Applicative(η) | Strategy::Normal(η) | Strategy::HeadSpine(η) => η,
+            _ => false,
+        }
+    }
 }
 
 impl Expression {
@@ -31,9 +211,114 @@ impl Expression {
     /// - η: λx.(e1 x) -> e1 whenever x does not appear free in e1
     ///
     ///     Represents local completeness in natural deduction.
-    pub fn apply(&self, η: bool) -> Self {
-        dbg!(η);
-        unimplemented!()
+    ///
+    /// Returns `None` when the expression is already in normal form with
+    /// respect to the given strategy. Stepping until `None` agrees with
+    /// [`Expression::normalize`], up to the names of bound variables for
+    /// [`Strategy::CallByNeed`], which steps like [`Strategy::Normal`] since a
+    /// single term can't share its arguments.
+    ///
+    /// ```
+    /// use lalrpop_lambda::Strategy;
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let expression = parser.parse("(λx.λy.x y) a b").unwrap();
+    /// let strategy = Strategy::Normal(false);
+    ///
+    /// let once = expression.apply(&strategy).unwrap();
+    /// assert_eq!(parser.parse("(λy.a y) b").unwrap(), once);
+    /// let twice = once.apply(&strategy).unwrap();
+    /// assert_eq!(parser.parse("a b").unwrap(), twice);
+    /// assert_eq!(None, twice.apply(&strategy));
+    /// ```
+    pub fn apply(&self, strategy: &impl ReductionStrategy) -> Option<Self> {
+        self.reduce(strategy, &mut Stats::default())
+    }
+
+    // A single step of `apply`, counting its work.
+    fn reduce<S: ReductionStrategy>(&self, strategy: &S, stats: &mut Stats) -> Option<Self> {
+        match self {
+            Expression::Var(_) => None,
+            Expression::Abs(Abstraction(id, box body)) => {
+                let inner = strategy.body()?;
+
+                // η-reduction
+                if let Expression::App(Application(box e1, box Expression::Var(x))) = body {
+                    if strategy.eta() && id == x && !e1.free_variables().contains(id) {
+                        stats.eta_steps += 1;
+                        return Some(e1.clone());
+                    }
+                }
+
+                body.reduce(&inner, stats)
+                    .map(|body| Expression::Abs(Abstraction(id.clone(), Box::new(body))))
+            }
+            Expression::App(Application(box e1, box e2)) => {
+                if let Some(e1) = e1.reduce(&strategy.head(), stats) {
+                    return Some(Expression::App(Application(
+                        Box::new(e1),
+                        Box::new(e2.clone()),
+                    )));
+                }
+
+                let redex = match e1 {
+                    Expression::Abs(abstraction) if strategy.beta() => Some(abstraction),
+                    _ => None,
+                };
+                if let Some(Abstraction(id, body)) = redex {
+                    if let Some(argument) = strategy.argument() {
+                        if let Some(e2) = e2.reduce(&argument, stats) {
+                            return Some(Expression::App(Application(
+                                Box::new(e1.clone()),
+                                Box::new(e2),
+                            )));
+                        }
+                    }
+
+                    // β-reduction
+                    stats.beta_steps += 1;
+                    return Some(body.substitute_free(e2, id, &e2.free_variables(), stats));
+                }
+
+                let neutral = strategy.neutral()?;
+                if let Some(e1) = e1.reduce(&neutral, stats) {
+                    Some(Expression::App(Application(
+                        Box::new(e1),
+                        Box::new(e2.clone()),
+                    )))
+                } else {
+                    e2.reduce(&neutral, stats)
+                        .map(|e2| Expression::App(Application(Box::new(e1.clone()), Box::new(e2))))
+                }
+            }
+        }
+    }
+
+    /// An iterator over each small-step reduction of this expression, see
+    /// [`Expression::apply`]
+    ///
+    /// The expression itself is not included, the last expression yielded is
+    /// the normal form.
+    ///
+    /// ```
+    /// use lalrpop_lambda::Strategy;
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let expression = parser.parse("(λx.λy.x y) a b").unwrap();
+    ///
+    /// let trace: Vec<_> = expression.reductions(&Strategy::Normal(false)).collect();
+    /// assert_eq!(vec![
+    ///     parser.parse("(λy.a y) b").unwrap(),
+    ///     parser.parse("a b").unwrap(),
+    /// ], trace);
+    /// ```
+    pub fn reductions<'a, S: ReductionStrategy>(&self, strategy: &'a S) -> Reductions<'a, S> {
+        Reductions {
+            expression: Some(self.clone()),
+            strategy,
+        }
     }
 
     /// Big-step natural semantics (⇓)
@@ -54,6 +339,7 @@ impl Expression {
     ///
     /// assert_eq!(normal, expression.normalize(&Strategy::Applicative(true)));
     /// assert_eq!(normal, expression.normalize(&Strategy::HeadSpine(false)));
+    /// assert_eq!(normal, expression.normalize(&Strategy::CallByNeed));
     /// ```
     pub fn normalize(&self, strategy: &Strategy) -> Self {
         match *strategy {
@@ -62,8 +348,198 @@ impl Expression {
             Strategy::CallByValue => self.bv(),
             Strategy::Applicative(η) => self.ao(η),
             Strategy::HeadSpine(η) => self.hs(η),
-            _ => unimplemented!(),
+            Strategy::HybridApplicative => self.ha(),
+            Strategy::HybridNormal => self.hn(),
+            Strategy::CallByNeed => {
+                match self.call_by_need(&Limits::default(), &mut Stats::default()) {
+                    Ok(e) => e,
+                    Err(_) => unreachable!("unbounded"),
+                }
+            }
+        }
+    }
+
+    /// Big-step semantics of your own [`ReductionStrategy`]
+    ///
+    /// Steps with [`Expression::apply`] until the term can't be reduced, which
+    /// for the built in strategies agrees with [`Expression::normalize`], up
+    /// to the names of bound variables for [`Strategy::CallByNeed`].
+    ///
+    /// ```
+    /// use lalrpop_lambda::Strategy;
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let expression = parser.parse("(λx.(λy.x y) b) a").unwrap();
+    /// let strategy = Strategy::HybridNormal;
+    /// assert_eq!(expression.normalize(&strategy), expression.normalize_with(&strategy));
+    /// ```
+    pub fn normalize_with(&self, strategy: &impl ReductionStrategy) -> Self {
+        match self.steps(strategy, &Limits::default(), &mut Stats::default()) {
+            Ok(e) => e,
+            Err(_) => unreachable!("unbounded"),
+        }
+    }
+
+    /// Big-step semantics which gives up instead of diverging
+    ///
+    /// Reduces one step at a time with [`Expression::apply`] using at most
+    /// `fuel` steps, returning the partially reduced expression if the fuel
+    /// runs out.
+    ///
+    /// ```
+    /// use lalrpop_lambda::{Limit, Strategy};
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let strategy = Strategy::Normal(false);
+    ///
+    /// let expression = parser.parse("(λx.x) a").unwrap();
+    /// assert_eq!(Ok(parser.parse("a").unwrap()),
+    ///            expression.normalize_with_limit(&strategy, 1));
+    ///
+    /// let Ω = parser.parse("(λx.x x) (λx.x x)").unwrap();
+    /// let exhausted = Ω.normalize_with_limit(&strategy, 100).unwrap_err();
+    /// assert_eq!(Limit::Steps(100), exhausted.limit);
+    /// assert_eq!(Ω, exhausted.expression);
+    /// ```
+    pub fn normalize_with_limit(
+        &self,
+        strategy: &Strategy,
+        fuel: usize,
+    ) -> Result<Self, Exhausted> {
+        let limits = Limits {
+            steps: Some(fuel),
+            ..Limits::default()
+        };
+        self.normalize_with_limits(strategy, &limits)
+    }
+
+    /// Big-step semantics bounded by the number of steps, and the size and
+    /// depth of each intermediate term
+    ///
+    /// Bounding the depth keeps the recursion of each step from overflowing
+    /// the stack.
+    ///
+    /// [`Strategy::CallByNeed`] evaluates a graph instead of stepping terms, so
+    /// its steps count β-reductions of shared thunks, its depth bounds the
+    /// nesting of thunks being forced, and only the normal form is bounded by
+    /// size. When it runs out, the `expression` is its graph read back as it
+    /// was left, with the arguments it shares bound by β-redexes.
+    ///
+    /// ```
+    /// use lalrpop_lambda::{Limit, Limits, Strategy};
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let limits = Limits { size: Some(100), ..Limits::default() };
+    ///
+    /// // (λx.x x x) (λx.x x x) grows forever.
+    /// let expression = parser.parse("(λx.x x x) (λx.x x x)").unwrap();
+    /// let exhausted = expression
+    ///     .normalize_with_limits(&Strategy::Normal(false), &limits)
+    ///     .unwrap_err();
+    /// assert_eq!(Limit::Size(100), exhausted.limit);
+    /// assert!(exhausted.expression.size() <= 100);
+    /// ```
+    pub fn normalize_with_limits(
+        &self,
+        strategy: &Strategy,
+        limits: &Limits,
+    ) -> Result<Self, Exhausted> {
+        self.normalize_with_stats(strategy, limits, &mut Stats::default())
+    }
+
+    /// Big-step semantics bounded by [`Limits`], collecting [`Stats`] of the
+    /// work done
+    ///
+    /// The `stats` are added to, so one collector can total several runs.
+    /// [`Strategy::CallByNeed`] has no intermediate terms, so it only counts
+    /// β-reductions and the binders renamed when reading back its normal form,
+    /// whose size and depth are the peak.
+    ///
+    /// ```
+    /// use lalrpop_lambda::{Limits, Stats, Strategy};
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let expression = parser.parse("(λx.x x) ((λy.y) z)").unwrap();
+    ///
+    /// let stats = |strategy| {
+    ///     let mut stats = Stats::default();
+    ///     expression.normalize_with_stats(&strategy, &Limits::default(), &mut stats).unwrap();
+    ///     stats
+    /// };
+    ///
+    /// // Normal order reduces the copied argument twice.
+    /// let normal = stats(Strategy::Normal(false));
+    /// assert_eq!((3, 4), (normal.beta_steps, normal.substitutions));
+    /// let by_value = stats(Strategy::CallByValue);
+    /// assert_eq!((2, 3), (by_value.beta_steps, by_value.substitutions));
+    /// ```
+    pub fn normalize_with_stats(
+        &self,
+        strategy: &Strategy,
+        limits: &Limits,
+        stats: &mut Stats,
+    ) -> Result<Self, Exhausted> {
+        if let Strategy::CallByNeed = strategy {
+            return self.call_by_need(limits, stats);
+        }
+        self.steps(strategy, limits, stats)
+    }
+
+    // Small steps of any strategy, within the limits.
+    fn steps<S: ReductionStrategy>(
+        &self,
+        strategy: &S,
+        limits: &Limits,
+        stats: &mut Stats,
+    ) -> Result<Self, Exhausted> {
+        let exceeded = |size, depth| match *limits {
+            Limits {
+                size: Some(limit), ..
+            } if size > limit => Some(Limit::Size(limit)),
+            Limits {
+                depth: Some(limit), ..
+            } if depth > limit => Some(Limit::Depth(limit)),
+            _ => None,
+        };
+
+        let mut expression = self.clone();
+        let (size, depth) = (expression.size(), expression.depth());
+        if let Some(limit) = exceeded(size, depth) {
+            return Err(Exhausted { limit, expression });
+        }
+        stats.reach(size, depth);
+
+        let mut steps = 0;
+        loop {
+            let mut step = Stats::default();
+            let next = match expression.reduce(strategy, &mut step) {
+                Some(next) => next,
+                None => break,
+            };
+
+            let (size, depth) = (next.size(), next.depth());
+            if let Some(limit) = exceeded(size, depth) {
+                return Err(Exhausted { limit, expression });
+            }
+
+            if limits.steps == Some(steps) {
+                return Err(Exhausted {
+                    limit: Limit::Steps(steps),
+                    expression,
+                });
+            }
+
+            stats.add(&step);
+            stats.reach(size, depth);
+            expression = next;
+            steps += 1;
         }
+
+        Ok(expression)
     }
 
     fn bn(&self) -> Self {
@@ -99,7 +575,7 @@ impl Expression {
     fn bv(&self) -> Self {
         match self {
             Expression::App(Application(box e1, box e2)) => match e1.bv() {
-                Expression::Abs(Abstraction(id, body)) => body.substitute(&e2.bv(), &id),
+                Expression::Abs(Abstraction(id, body)) => body.substitute(&e2.bv(), &id).bv(),
                 e @ _ => Expression::App(Application(Box::new(e), Box::new(e2.bv()))),
             },
             _ => self.clone(),
@@ -138,35 +614,89 @@ impl Expression {
 
                 Expression::Abs(Abstraction(id.clone(), Box::new(body.hs(η))))
             }
-            Expression::App(Application(box e1, box e2)) => match e1.bn() {
-                Expression::Abs(Abstraction(id, body)) => body.substitute(&e2, &id),
+            Expression::App(Application(box e1, box e2)) => match e1.hs(η) {
+                Expression::Abs(Abstraction(id, body)) => body.substitute(&e2, &id).hs(η),
                 e @ _ => Expression::App(Application(Box::new(e), Box::new(e2.clone()))),
             },
             _ => self.clone(),
         }
     }
 
+    fn ha(&self) -> Self {
+        match self {
+            Expression::Var(_) => self.clone(),
+            Expression::Abs(Abstraction(id, box body)) => {
+                Expression::Abs(Abstraction(id.clone(), Box::new(body.ha())))
+            }
+            Expression::App(Application(box e1, box e2)) => match e1.bv() {
+                Expression::Abs(Abstraction(id, body)) => body.substitute(&e2.ha(), &id).ha(),
+                e => Expression::App(Application(Box::new(e.ha()), Box::new(e2.ha()))),
+            },
+        }
+    }
+
+    fn hn(&self) -> Self {
+        match self {
+            Expression::Var(_) => self.clone(),
+            Expression::Abs(Abstraction(id, box body)) => {
+                Expression::Abs(Abstraction(id.clone(), Box::new(body.hn())))
+            }
+            Expression::App(Application(box e1, box e2)) => match e1.hs(false) {
+                Expression::Abs(Abstraction(id, body)) => body.substitute(e2, &id).hn(),
+                e => Expression::App(Application(Box::new(e.hn()), Box::new(e2.hn()))),
+            },
+        }
+    }
+
     /// self[x := v]
-    fn substitute(&self, v: &Self, x: &Variable) -> Self {
+    pub(crate) fn substitute(&self, v: &Self, x: &Variable) -> Self {
+        self.substitute_free(v, x, &v.free_variables(), &mut Stats::default())
+    }
+
+    /// self[x := v], given the free variables of `v`, so they're only found
+    /// once
+    fn substitute_free(
+        &self,
+        v: &Self,
+        x: &Variable,
+        free: &HashSet<Variable>,
+        stats: &mut Stats,
+    ) -> Self {
         match self {
+            // Variables are matched by name, type annotations only appear on
+            // binders.
+            Expression::Abs(Abstraction(id, _)) if id.0 == x.0 => self.clone(),
             Expression::Abs(Abstraction(id, box body)) => {
-                if id == x || !v.free_variables().contains(id) {
-                    Expression::Abs(Abstraction(id.clone(), Box::new(body.substitute(v, x))))
+                if !free.iter().any(|v| v.0 == id.0) {
+                    let body = body.substitute_free(v, x, free, stats);
+                    Expression::Abs(Abstraction(id.clone(), Box::new(body)))
+                } else if !body.free_variables().iter().any(|v| v.0 == x.0) {
+                    // Nothing would be substituted, so nothing can be captured.
+                    self.clone()
                 } else {
-                    let fresh = Variable(format!("{}'", id), None);
-                    let body = body.replace(&id, &fresh);
-                    Expression::Abs(Abstraction(fresh, Box::new(body.substitute(v, x))))
+                    // The fresh name mustn't be `x` either, or the renamed
+                    // variables would be substituted too.
+                    let mut avoid: HashSet<_> = free.union(&body.variables()).cloned().collect();
+                    avoid.insert(x.clone());
+                    let fresh = id.fresh(&avoid);
+                    stats.renames += 1;
+                    let body = body.replace(&id, &fresh).substitute_free(v, x, free, stats);
+                    Expression::Abs(Abstraction(fresh, Box::new(body)))
                 }
             }
-            Expression::Var(id) => (if id == x { v } else { self }).clone(),
+            Expression::Var(id) if id.0 == x.0 => {
+                stats.substitutions += 1;
+                v.clone()
+            }
+            Expression::Var(_) => self.clone(),
             Expression::App(Application(e1, e2)) => Expression::App(Application(
-                Box::new(e1.substitute(v, x)),
-                Box::new(e2.substitute(v, x)),
+                Box::new(e1.substitute_free(v, x, free, stats)),
+                Box::new(e2.substitute_free(v, x, free, stats)),
             )),
         }
     }
 
-    fn replace(&self, old: &Variable, new: &Variable) -> Self {
+    pub(crate) fn replace(&self, old: &Variable, new: &Variable) -> Self {
         match self {
             Expression::Var(v) => Expression::Var(v.replace(old, new)),
             Expression::Abs(Abstraction(id, body)) => Expression::Abs(Abstraction(
@@ -181,10 +711,49 @@ impl Expression {
     }
 }
 
+/// An iterator over the small-step reductions of an [`Expression`]
+///
+/// See [`Expression::reductions`].
+pub struct Reductions<'a, S = Strategy> {
+    expression: Option<Expression>,
+    strategy: &'a S,
+}
+
+impl<'a, S: ReductionStrategy> Iterator for Reductions<'a, S> {
+    type Item = Expression;
+
+    fn next(&mut self) -> Option<Expression> {
+        let next = self.expression.as_ref()?.apply(self.strategy);
+        self.expression = next.clone();
+        next
+    }
+}
+
+impl fmt::Display for Exhausted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.limit {
+            Limit::Steps(n) => write!(f, "exceeded {} reduction steps", n),
+            Limit::Size(n) => write!(f, "exceeded a term size of {}", n),
+            Limit::Depth(n) => write!(f, "exceeded a term depth of {}", n),
+        }
+    }
+}
+
+impl Error for Exhausted {}
+
 impl Variable {
+    /// A variable like this one, primed until its name isn't in `avoid`
+    pub(crate) fn fresh(&self, avoid: &HashSet<Variable>) -> Self {
+        let mut fresh = self.clone();
+        while fresh == *self || avoid.iter().any(|v| v.0 == fresh.0) {
+            fresh.0.push('\'');
+        }
+        fresh
+    }
+
     fn replace(&self, old: &Variable, new: &Variable) -> Self {
         if self.0 == old.0 {
-            Variable(new.0.clone(), new.1.clone())
+            Variable(new.0.clone(), self.1.clone())
         } else {
             self.clone()
         }
@@ -194,12 +763,67 @@ impl Variable {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{abs, app, var, variable};
+    use crate::parse::ExpressionParser;
+    use crate::{abs, app, var, variable, Alpha};
     use pretty_assertions::assert_eq;
 
     #[test]
-    #[ignore]
-    fn apply() {}
+    fn apply() {
+        let strategy = Strategy::Applicative(false);
+
+        assert_eq!(None, var!(a).apply(&strategy));
+        assert_eq!(None, abs! {x.x}.apply(&strategy));
+        assert_eq!(Some(var!(a)), app!(abs! {x.x}, a).apply(&strategy));
+        assert_eq!(
+            Some(app!(abs! {x.app!(a, a)}, b)),
+            app!(abs! {x.app!(abs!{x.app!(x, x)}, a)}, b).apply(&strategy)
+        );
+
+        // Innermost first.
+        let expression = app!(abs! {x.x}, app!(abs! {y.y}, a));
+        assert_eq!(
+            Some(app!(abs! {x.x}, a)),
+            expression.apply(&Strategy::Applicative(false))
+        );
+        assert_eq!(
+            Some(app!(abs! {y.y}, a)),
+            expression.apply(&Strategy::Normal(false))
+        );
+
+        // Weak strategies don't reduce under λ.
+        let expression = abs! {x.app!(abs!{y.y}, x)};
+        assert_eq!(None, expression.apply(&Strategy::CallByName));
+        assert_eq!(None, expression.apply(&Strategy::CallByValue));
+        assert_eq!(Some(abs! {x.x}), expression.apply(&Strategy::Normal(false)));
+        assert_eq!(Some(abs! {y.y}), expression.apply(&Strategy::Normal(true)));
+    }
+
+    #[test]
+    fn reductions() {
+        let expression = app!(app!(abs! {x.abs!{y.app!(x, y)}}, b), a);
+        for strategy in &[
+            Strategy::CallByName,
+            Strategy::Normal(false),
+            Strategy::Applicative(false),
+        ] {
+            assert_eq!(
+                vec![app!(abs! {y.app!(b, y)}, a), app!(b, a)],
+                expression.reductions(strategy).collect::<Vec<_>>()
+            );
+        }
+
+        let expression = app!(
+            abs! {n.abs!{f.abs!{x.app!(f, app!(app!(n, f), x))}}},
+            app!(abs! {x.x}, abs! {f.abs!{x.x}})
+        );
+        for strategy in &[Strategy::Normal(false), Strategy::Applicative(false)] {
+            assert_eq!(
+                Some(expression.normalize(strategy)),
+                expression.reductions(strategy).last()
+            );
+        }
+        assert_eq!(0, var!(x).reductions(&Strategy::Normal(false)).count());
+    }
 
     #[test]
     fn normalize() {
@@ -278,7 +902,7 @@ mod tests {
                 abs! {f.abs!{x.x}}
             )
         );
-        assert_eq!(expected, actual.normalize(&strategy));
+        assert_eq!(Alpha(expected), Alpha(actual.normalize(&strategy)));
     }
 
     #[test]
@@ -291,16 +915,350 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     #[allow(non_snake_case)]
     fn normalize_Ω() {
-        let strategy = Strategy::Applicative(false);
+        // Ω reduces to itself forever, without growing.
+        let Ω = app!(abs! {x.app!(x,x)}, abs! {x.app!(x,x)});
+        let limits = Limits {
+            steps: Some(1000),
+            size: Some(Ω.size()),
+            depth: Some(Ω.depth()),
+        };
+        for strategy in &[
+            Strategy::Applicative(false),
+            Strategy::Applicative(true),
+            Strategy::CallByValue,
+            Strategy::HybridApplicative,
+            Strategy::CallByName,
+            Strategy::Normal(false),
+            Strategy::Normal(true),
+            Strategy::HeadSpine(false),
+            Strategy::HybridNormal,
+            Strategy::CallByNeed,
+        ] {
+            assert_eq!(
+                Err(Exhausted {
+                    limit: Limit::Steps(1000),
+                    expression: Ω.clone(),
+                }),
+                Ω.normalize_with_limits(strategy, &limits),
+                "{:?}",
+                strategy
+            );
+        }
+    }
 
+    #[test]
+    #[allow(non_snake_case)]
+    fn normalize_with_limit_Ω() {
         let Ω = app!(abs! {x.app!(x,x)}, abs! {x.app!(x,x)});
-        assert_eq!(abs! {x.x}, Ω.normalize(&strategy));
+        for strategy in &[
+            Strategy::CallByName,
+            Strategy::CallByValue,
+            Strategy::Normal(false),
+            Strategy::Applicative(false),
+            Strategy::CallByNeed,
+        ] {
+            let exhausted = Ω.normalize_with_limit(strategy, 1000).unwrap_err();
+            assert_eq!(Limit::Steps(1000), exhausted.limit);
+            assert_eq!(Ω, exhausted.expression);
+        }
     }
 
-    // TODO: Strategy testing.
+    #[test]
+    fn normalize_with_limits() {
+        let strategy = Strategy::Normal(false);
+        let expression = app!(app!(abs! {x.abs!{y.app!(x, y)}}, b), a);
+
+        assert_eq!(
+            Ok(app!(b, a)),
+            expression.normalize_with_limit(&strategy, 2)
+        );
+        assert_eq!(
+            Err(Exhausted {
+                limit: Limit::Steps(1),
+                expression: app!(abs! {y.app!(b, y)}, a),
+            }),
+            expression.normalize_with_limit(&strategy, 1)
+        );
+
+        let limits = Limits {
+            depth: Some(1),
+            ..Limits::default()
+        };
+        assert_eq!(
+            Err(Exhausted {
+                limit: Limit::Depth(1),
+                expression: expression.clone(),
+            }),
+            expression.normalize_with_limits(&strategy, &limits)
+        );
+
+        // A growing term, (λx.x x x) (λx.x x x).
+        let ω3 = abs! {x.app!(app!(x, x), x)};
+        let expression = app!({ &ω3 }, { &ω3 });
+        let limits = Limits {
+            steps: Some(1000),
+            depth: Some(50),
+            ..Limits::default()
+        };
+        let exhausted = expression
+            .normalize_with_limits(&strategy, &limits)
+            .unwrap_err();
+        assert_eq!(Limit::Depth(50), exhausted.limit);
+        assert!(exhausted.expression.depth() <= 50);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn normalize_with_stats() {
+        let parser = ExpressionParser::new();
+        let stats = |source, strategy| {
+            let mut stats = Stats::default();
+            parser
+                .parse(source)
+                .unwrap()
+                .normalize_with_stats(&strategy, &Limits::default(), &mut stats)
+                .unwrap();
+            stats
+        };
+
+        // An unused argument is only reduced by innermost strategies.
+        let expression = r"(λx.y) ((λx.x) (λx.x))";
+        assert_eq!(1, stats(expression, Strategy::CallByName).beta_steps);
+        assert_eq!(
+            2,
+            stats(expression, Strategy::Applicative(false)).beta_steps
+        );
+
+        assert_eq!(
+            Stats {
+                beta_steps: 1,
+                eta_steps: 1,
+                substitutions: 2,
+                renames: 0,
+                peak_size: 9,
+                max_depth: 5,
+            },
+            stats(r"(λx.λy.x x y) f", Strategy::Normal(true))
+        );
+        let renamed = stats(r"(λx.λy.x) y", Strategy::Normal(false));
+        assert_eq!((1, 1), (renamed.substitutions, renamed.renames));
+        // A binder is only renamed when there's something to substitute under it.
+        let unused = stats(r"(λx.λy.z) y", Strategy::Normal(false));
+        assert_eq!((0, 0), (unused.substitutions, unused.renames));
+
+        // Call by need shares the argument instead of substituting it.
+        let shared = stats(r"(λx.x x) ((λy.y) z)", Strategy::CallByNeed);
+        assert_eq!((2, 0), (shared.beta_steps, shared.substitutions));
+
+        // Work is counted up to the limit, and collectors add up.
+        let Ω = app!(abs! {x.app!(x,x)}, abs! {x.app!(x,x)});
+        let mut stats = Stats::default();
+        for _ in 0..2 {
+            let limits = Limits {
+                steps: Some(10),
+                ..Limits::default()
+            };
+            assert!(Ω
+                .normalize_with_stats(&Strategy::Normal(false), &limits, &mut stats)
+                .is_err());
+        }
+        assert_eq!((20, 40), (stats.beta_steps, stats.substitutions));
+        assert_eq!((Ω.size(), Ω.depth()), (stats.peak_size, stats.max_depth));
+    }
+
+    const STRATEGIES: [Strategy; 11] = [
+        Strategy::CallByName,
+        Strategy::Normal(false),
+        Strategy::Normal(true),
+        Strategy::CallByValue,
+        Strategy::Applicative(false),
+        Strategy::Applicative(true),
+        Strategy::HeadSpine(false),
+        Strategy::HeadSpine(true),
+        Strategy::HybridApplicative,
+        Strategy::HybridNormal,
+        Strategy::CallByNeed,
+    ];
+
+    #[test]
+    fn strategies() {
+        let parser = ExpressionParser::new();
+        let normalize = |e: &str, s: &Strategy| {
+            parser
+                .parse(e)
+                .unwrap()
+                .normalize_with_limit(s, 1000)
+                .map_err(|_| ())
+        };
+        let parse = |e: &str| Ok(parser.parse(e).unwrap());
+
+        // Results in order of `STRATEGIES`, `Err` when diverging.
+        #[rustfmt::skip]
+        let table = vec![
+            (r"(λx.y) ((λx.x x) (λx.x x))", vec![
+                parse("y"), parse("y"), parse("y"),
+                Err(()), Err(()), Err(()),
+                parse("y"), parse("y"),
+                Err(()), parse("y"),
+                parse("y"),
+            ]),
+            (r"λx.(λy.y) x", vec![
+                parse(r"λx.(λy.y) x"), parse(r"λx.x"), parse(r"λy.y"),
+                parse(r"λx.(λy.y) x"), parse(r"λx.x"), parse(r"λy.y"),
+                parse(r"λx.x"), parse(r"λy.y"),
+                parse(r"λx.x"), parse(r"λx.x"),
+                parse(r"λx.x"),
+            ]),
+            (r"x ((λy.y) z)", vec![
+                parse(r"x ((λy.y) z)"), parse(r"x z"), parse(r"x z"),
+                parse(r"x z"), parse(r"x z"), parse(r"x z"),
+                parse(r"x ((λy.y) z)"), parse(r"x ((λy.y) z)"),
+                parse(r"x z"), parse(r"x z"),
+                parse(r"x z"),
+            ]),
+            (r"(λx.x) (λy.(λz.z) y)", vec![
+                parse(r"λy.(λz.z) y"), parse(r"λy.y"), parse(r"λz.z"),
+                parse(r"λy.(λz.z) y"), parse(r"λy.y"), parse(r"λz.z"),
+                parse(r"λy.y"), parse(r"λz.z"),
+                parse(r"λy.y"), parse(r"λy.y"),
+                parse(r"λy.y"),
+            ]),
+            (r"(λx.λy.x y) a b", vec![
+                parse(r"a b"), parse(r"a b"), parse(r"a b"),
+                parse(r"a b"), parse(r"a b"), parse(r"a b"),
+                parse(r"a b"), parse(r"a b"),
+                parse(r"a b"), parse(r"a b"),
+                parse(r"a b"),
+            ]),
+        ];
+
+        for (expression, expected) in table {
+            for (strategy, expected) in STRATEGIES.iter().zip(expected) {
+                assert_eq!(
+                    expected,
+                    normalize(expression, strategy),
+                    "{} with {:?}",
+                    expression,
+                    strategy
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn weak_and_head_normal_forms() {
+        let parser = ExpressionParser::new();
+        let normalize = |e: &str, s: &Strategy| parser.parse(e).unwrap().normalize(s);
+
+        // A substitution can make a new redex, which is reduced too, where
+        // call by value and head spine used to stop after one substitution.
+        assert_eq!(
+            parser.parse(r"λy.y").unwrap(),
+            normalize(r"(λx.x x) (λy.y)", &Strategy::CallByValue)
+        );
+        assert_eq!(
+            parser.parse(r"λy.y").unwrap(),
+            normalize(r"(λx.x) (λy.(λz.z) y)", &Strategy::HeadSpine(false))
+        );
+    }
+
+    #[test]
+    fn reductions_agree_with_normalize() {
+        let parser = ExpressionParser::new();
+        let expressions = [
+            r"(λx.y) ((λx.x x) (λx.x x))",
+            r"λx.(λy.y) x",
+            r"x ((λy.y) z)",
+            r"(λx.x) (λy.(λz.z) y)",
+            r"(λn.λf.λx.f (n f x)) ((λn.λf.λx.f (n f x)) (λf.λx.x))",
+            r"(λx.x x) ((λy.y) (λz.z))",
+            r"(λf.λx.f (f x)) (λf.λx.f (f x))",
+        ];
+
+        for expression in &expressions {
+            let expression = parser.parse(expression).unwrap();
+            for strategy in &STRATEGIES {
+                if let Ok(normal) = expression.normalize_with_limit(strategy, 1000) {
+                    assert_eq!(
+                        normal,
+                        expression.normalize(strategy),
+                        "{} with {:?}",
+                        expression,
+                        strategy
+                    );
+                    assert!(
+                        normal.alpha_eq(&expression.normalize_with(strategy)),
+                        "{} with {:?}",
+                        expression,
+                        strategy
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reduction_strategy() {
+        // η-reduction alone.
+        struct Eta;
+
+        impl ReductionStrategy for Eta {
+            fn body(&self) -> Option<Self> {
+                Some(Eta)
+            }
+
+            fn head(&self) -> Self {
+                Eta
+            }
+
+            fn neutral(&self) -> Option<Self> {
+                Some(Eta)
+            }
+
+            fn beta(&self) -> bool {
+                false
+            }
+
+            fn eta(&self) -> bool {
+                true
+            }
+        }
+
+        let parser = ExpressionParser::new();
+        let normalize = |source| parser.parse(source).unwrap().normalize_with(&Eta);
+        assert_eq!(var!(f), normalize(r"λx.f x"));
+        assert_eq!(app!(f, a), normalize(r"(λx.f x) a"));
+        assert_eq!(abs! {y.y}, normalize(r"λx.(λy.y) x"));
+        assert_eq!(
+            parser.parse(r"(λx.x x) a").unwrap(),
+            normalize(r"(λx.x x) a")
+        );
+        assert_eq!(None, var!(f).apply(&Eta));
+        let e = parser.parse(r"λx.λy.f x y").unwrap();
+        assert_eq!(
+            vec![abs! {x.app!(f, x)}, var!(f)],
+            e.reductions(&Eta).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn substitute() {
+        let z = Variable("z'".into(), None);
+        assert_eq!(abs! {x.y}, abs! {x.z}.substitute(&var!(y), &variable!(z)));
+        assert_eq!(abs! {z.z}, abs! {z.z}.substitute(&var!(y), &variable!(z)));
+
+        // A renamed binder avoids the variable being substituted too.
+        let identity = abs! {z.z}.substitute(&var!(z), &z);
+        assert!(identity.alpha_eq(&abs! {x.x}), "{}", identity);
+        let e = Expression::Abs(Abstraction(
+            variable!(z),
+            Box::new(Expression::Var(z.clone())),
+        ));
+        let constant = e.substitute(&var!(z), &z);
+        assert!(constant.alpha_eq(&abs! {x.z}), "{}", constant);
+    }
 
     #[test]
     fn replace() {
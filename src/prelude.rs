@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use lalrpop_util::ParseError;
+
+use crate::parse::{DefinitionsParser, ProgramParser, Token};
+use crate::{Expression, Variable};
+
+/// The definitions of the prelude, in the order they are resolved
+///
+/// Each term may only refer to the names defined above it. The encodings agree
+/// with the `From` and `TryFrom` conversions in `mod encode`.
+const SOURCE: &str = r"
+I = λx.x;
+K = λx y.x;
+S = λx y z.x z (y z);
+B = λx y z.x (y z);
+C = λx y z.x z y;
+W = λx y.x y y;
+
+Y = λf.(λx.f (x x)) (λx.f (x x));
+Z = λf.(λx.f (λv.x x v)) (λx.f (λv.x x v));
+Theta = (λx f.f (x x f)) (λx f.f (x x f));
+
+true = λa b.a;
+false = λa b.b;
+if = λp a b.p a b;
+not = λp a b.p b a;
+and = λp q.p q p;
+or = λp q.p p q;
+xor = λp q.p (not q) q;
+
+pair = λa b p.p a b;
+fst = λp.p true;
+snd = λp.p false;
+
+zero = λf x.x;
+succ = λn f x.f (n f x);
+pred = λn.fst (n (λp.pair (snd p) (succ (snd p))) (pair zero zero));
+add = λm n f x.m f (n f x);
+sub = λm n.n pred m;
+mul = λm n f.m (n f);
+pow = λm n f x.n m f x;
+is_zero = λn.n (λx.false) true;
+leq = λm n.is_zero (sub m n);
+eq = λm n.and (leq m n) (leq n m);
+
+nil = λc n.n;
+cons = λh t c n.c h (t c n);
+is_nil = λl.l (λh t.false) true;
+fold = λf z l.l f z;
+map = λf l c n.l (λh t.c (f h) t) n;
+length = λl.l (λh.succ) zero;
+sum = λl.l add zero;
+";
+
+/// The standard combinators, along with operations on booleans, numerals,
+/// pairs and lists
+///
+/// A `Prelude` dereferences to the `HashMap` of its definitions, so it can be
+/// given directly to [`Expression::resolve`].
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use lalrpop_lambda::Prelude;
+/// use lalrpop_lambda::parse::ExpressionParser;
+///
+/// let prelude = Prelude::new();
+/// let e = ExpressionParser::new().parse("mul (succ (succ zero)) (succ zero)").unwrap();
+/// assert_eq!(Ok(2), u64::try_from(e.resolve(&prelude)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Prelude(HashMap<Variable, Expression>);
+
+impl Prelude {
+    /// The built in prelude
+    pub fn new() -> Self {
+        let mut prelude = Prelude(HashMap::new());
+        prelude.define(SOURCE).expect("the prelude parses");
+        prelude
+    }
+
+    /// Add each `name = term;` definition of `source` to the prelude
+    ///
+    /// Definitions are resolved in order, so each term may refer to the names
+    /// defined before it. Later definitions replace earlier ones.
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::{Expression, Prelude};
+    ///
+    /// let mut prelude = Prelude::new();
+    /// prelude.define("two = succ (succ zero); four = add two two;").unwrap();
+    /// let four = prelude.parse("four").unwrap();
+    /// assert_eq!(Ok(4), u64::try_from(four));
+    /// ```
+    pub fn define<'a>(
+        &mut self,
+        source: &'a str,
+    ) -> Result<(), ParseError<usize, Token<'a>, &'static str>> {
+        for (id, e) in DefinitionsParser::new().parse(source)? {
+            let e = e.resolve(&self.0);
+            self.0.insert(id, e);
+        }
+        Ok(())
+    }
+
+    /// Parse a program, resolving the names of the prelude in it
+    ///
+    /// Each definition is only bound in the definitions after it and the main
+    /// term, not in itself, so for recursion see
+    /// [`Expression::resolve_recursive`].
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::Prelude;
+    ///
+    /// let prelude = Prelude::new();
+    /// let e = prelude.parse("id = I; id (not false)").unwrap();
+    /// assert_eq!(Ok(true), bool::try_from(e));
+    /// ```
+    pub fn parse<'a>(
+        &self,
+        source: &'a str,
+    ) -> Result<Expression, ParseError<usize, Token<'a>, &'static str>> {
+        ProgramParser::new()
+            .parse(source)
+            .map(|e| e.resolve(&self.0))
+    }
+}
+
+impl Default for Prelude {
+    fn default() -> Self {
+        Prelude::new()
+    }
+}
+
+impl Deref for Prelude {
+    type Target = HashMap<Variable, Expression>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Prelude> for HashMap<Variable, Expression> {
+    fn from(prelude: Prelude) -> Self {
+        prelude.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::Strategy;
+
+    fn eval(prelude: &Prelude, source: &str) -> Expression {
+        prelude
+            .parse(source)
+            .unwrap()
+            .normalize(&Strategy::Normal(false))
+    }
+
+    #[test]
+    fn closed() {
+        for (id, e) in Prelude::new().iter() {
+            assert!(e.free_variables().is_empty(), "{} is open", id);
+        }
+    }
+
+    #[test]
+    fn combinators() {
+        let prelude = Prelude::new();
+        let i = prelude[&variable!(I)].clone();
+        assert!(eval(&prelude, "S K K").alpha_eq(&i));
+        assert!(eval(&prelude, "B I I").alpha_eq(&i));
+        assert_eq!(var!(b), eval(&prelude, "C K a b"));
+        assert_eq!(app!(app!(a, b), b), eval(&prelude, "W a b"));
+    }
+
+    #[test]
+    fn not_recursive() {
+        let prelude = Prelude::new();
+        let e = prelude.parse("f = λn.f n; f").unwrap();
+        assert!(e.free_variables().contains(&variable!(f)));
+    }
+
+    #[test]
+    fn fixed_points() {
+        let mut prelude = Prelude::new();
+        prelude
+            .define("triangle = λf n.if (is_zero n) zero (add n (f (pred n)));")
+            .unwrap();
+        prelude.define("three = succ (succ (succ zero));").unwrap();
+        for fix in &["Y", "Z", "Theta"] {
+            let e = eval(&prelude, &format!("{} triangle three", fix));
+            assert_eq!(Ok(6), u64::try_from(e), "{}", fix);
+        }
+    }
+
+    #[test]
+    fn booleans() {
+        let prelude = Prelude::new();
+        for &(source, expected) in &[
+            ("not true", false),
+            ("and true false", false),
+            ("or false true", true),
+            ("xor true true", false),
+            ("if false false true", true),
+            ("is_zero zero", true),
+            ("is_nil (cons zero nil)", false),
+        ] {
+            assert_eq!(
+                Ok(expected),
+                bool::try_from(eval(&prelude, source)),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn numerals() {
+        let mut prelude = Prelude::new();
+        prelude
+            .define("two = succ (succ zero); three = succ two; l = cons two (cons three nil);")
+            .unwrap();
+        for &(source, expected) in &[
+            ("pred three", 2),
+            ("add two three", 5),
+            ("sub two three", 0),
+            ("mul two three", 6),
+            ("pow two three", 8),
+            ("pow zero zero", 1),
+            ("snd (pair two three)", 3),
+            ("length l", 2),
+            ("sum (map succ l)", 7),
+            ("fold mul (succ zero) l", 6),
+        ] {
+            assert_eq!(
+                Ok(expected),
+                u64::try_from(eval(&prelude, source)),
+                "{}",
+                source
+            );
+        }
+        assert_eq!(Ok(true), bool::try_from(eval(&prelude, "leq two three")));
+        assert_eq!(Ok(false), bool::try_from(eval(&prelude, "eq two three")));
+    }
+
+    #[test]
+    fn encodings() {
+        let prelude = Prelude::new();
+        assert!(prelude[&Variable("true".into(), None)].alpha_eq(&true.into()));
+        assert!(prelude[&variable!(zero)].alpha_eq(&0.into()));
+        let list = eval(&prelude, "cons zero (cons (succ zero) nil)");
+        assert_eq!(Ok(vec![0, 1]), Vec::<u64>::try_from(list));
+        let pair = eval(&prelude, "pair true zero");
+        assert_eq!(Ok((true, 0)), <(bool, u64)>::try_from(pair));
+    }
+}
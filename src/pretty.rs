@@ -0,0 +1,267 @@
+use std::fmt;
+
+use crate::{Abstraction, Application, Expression, Type, Variable};
+
+/// Options for displaying an [`Expression`]
+///
+/// The [`fmt::Display`] implementation of `Expression` uses the default style.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    /// Write `\` instead of `λ`, and `->` instead of `→`
+    pub ascii: bool,
+    /// Write nested abstractions as `λx y.e` instead of `λx.λy.e`
+    pub curry: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            ascii: false,
+            curry: true,
+        }
+    }
+}
+
+/// An [`Expression`], or a [`Type`], displayed with the fewest parentheses
+/// needed to parse it back again
+///
+/// Application is left associative, and abstractions extend as far right as
+/// possible, so parentheses are only needed around an abstraction being
+/// applied, and around an argument which isn't a variable.
+///
+/// ```
+/// use lalrpop_lambda::Style;
+/// use lalrpop_lambda::parse::ExpressionParser;
+///
+/// let parser = ExpressionParser::new();
+/// let expression = parser.parse(r"((λx.(λy.(x y))) (a (b c)))").unwrap();
+///
+/// assert_eq!("(λx y.x y) (a (b c))", expression.to_string());
+///
+/// let style = Style { ascii: true, curry: false };
+/// assert_eq!(r"(\x.\y.x y) (a (b c))", expression.pretty(style).to_string());
+///
+/// let expression = parser.parse(r"λf:a→a.f").unwrap();
+/// assert_eq!(r"\f:(a -> a).f", expression.pretty(style).to_string());
+/// ```
+pub struct Pretty<'a, T = Expression>(&'a T, Style);
+
+impl Expression {
+    /// Display this expression in the given [`Style`]
+    pub fn pretty(&self, style: Style) -> Pretty<'_> {
+        Pretty(self, style)
+    }
+}
+
+impl Variable {
+    /// Display this variable, and its type annotation, in the given [`Style`]
+    pub fn pretty(&self, style: Style) -> Pretty<'_, Variable> {
+        Pretty(self, style)
+    }
+}
+
+impl Type {
+    /// Display this type in the given [`Style`]
+    pub fn pretty(&self, style: Style) -> Pretty<'_, Type> {
+        Pretty(self, style)
+    }
+}
+
+impl<'a> fmt::Display for Pretty<'a, Type> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Pretty(ty, style) = *self;
+        match ty {
+            Type::Base(name) => write!(f, "{}", name),
+            Type::Arrow(t1, t2) => {
+                let arrow = if style.ascii { "->" } else { "→" };
+                match **t1 {
+                    Type::Arrow(..) => write!(f, "({}) {} ", t1.pretty(style), arrow)?,
+                    _ => write!(f, "{} {} ", t1.pretty(style), arrow)?,
+                }
+                write!(f, "{}", t2.pretty(style))
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Display for Pretty<'a, Variable> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Pretty(id, style) = *self;
+        match &id.1 {
+            Some(ty @ Type::Arrow(..)) => write!(f, "{}:({})", id.0, ty.pretty(style)),
+            Some(ty) => write!(f, "{}:{}", id.0, ty.pretty(style)),
+            None => write!(f, "{}", id.0),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Pretty<'a, Expression> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Pretty(expression, style) = *self;
+        match expression {
+            Expression::Var(id) => write!(f, "{}", id.pretty(style)),
+            Expression::Abs(Abstraction(id, body)) => {
+                let lambda = if style.ascii { "\\" } else { "λ" };
+                write!(f, "{}{}", lambda, id.pretty(style))?;
+
+                // Variables without names are only written by the parser for
+                // extra lambdas, like in `\\\`, which aren't curried.
+                let mut body = body;
+                if style.curry && !id.0.is_empty() {
+                    while let Expression::Abs(Abstraction(id, inner)) = &**body {
+                        if id.0.is_empty() {
+                            break;
+                        }
+                        write!(f, " {}", id.pretty(style))?;
+                        body = inner;
+                    }
+                }
+
+                write!(f, ".{}", body.pretty(style))
+            }
+            Expression::App(Application(e1, e2)) => {
+                if let Expression::Abs(_) = **e1 {
+                    write!(f, "({})", e1.pretty(style))?;
+                } else {
+                    write!(f, "{}", e1.pretty(style))?;
+                }
+
+                if let Expression::Var(_) = **e2 {
+                    write!(f, " {}", e2.pretty(style))
+                } else {
+                    write!(f, " ({})", e2.pretty(style))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::ExpressionParser;
+    use crate::{Strategy, Variable};
+    use pretty_assertions::assert_eq;
+
+    const STYLES: [Style; 4] = [
+        Style {
+            ascii: false,
+            curry: true,
+        },
+        Style {
+            ascii: false,
+            curry: false,
+        },
+        Style {
+            ascii: true,
+            curry: true,
+        },
+        Style {
+            ascii: true,
+            curry: false,
+        },
+    ];
+
+    #[test]
+    fn minimal_parentheses() {
+        let parser = ExpressionParser::new();
+        let display = |e| parser.parse(e).unwrap().to_string();
+
+        assert_eq!("x", display(r"(x)"));
+        assert_eq!("a b c", display(r"((a b) c)"));
+        assert_eq!("a (b c)", display(r"(a (b c))"));
+        assert_eq!("λx.x", display(r"(λx.x)"));
+        assert_eq!("λx.x y", display(r"(λx.(x y))"));
+        assert_eq!("(λx.x) y", display(r"((λx.x) y)"));
+        assert_eq!("a (λx.x)", display(r"(a (λx.x))"));
+        assert_eq!("a (λx.x) b", display(r"((a (λx.x)) b)"));
+        assert_eq!("λx y z.x z (y z)", display(r"λx.λy.λz.x z (y z)"));
+        assert_eq!("λx:t y:u.x", display(r"λx:t.λy:u.x"));
+        assert_eq!("λx.λ.λ.λ.", display(r"\x.\\\"));
+    }
+
+    #[test]
+    fn styles() {
+        let displays = |source| -> Vec<_> {
+            let expression = ExpressionParser::new().parse(source).unwrap();
+            STYLES
+                .iter()
+                .map(|s| expression.pretty(*s).to_string())
+                .collect()
+        };
+        assert_eq!(
+            vec![r"λx y.x", r"λx.λy.x", r"\x y.x", r"\x.\y.x"],
+            displays(r"λx.λy.x")
+        );
+        assert_eq!(
+            vec![
+                r"λf:(a → a) x:a.f x",
+                r"λf:(a → a).λx:a.f x",
+                r"\f:(a -> a) x:a.f x",
+                r"\f:(a -> a).\x:a.f x",
+            ],
+            displays(r"λf:a→a.λx:a.f x")
+        );
+    }
+
+    // Every expression with up to `size` nodes over the given variables.
+    fn expressions(size: usize, ids: &[&str]) -> Vec<Expression> {
+        let mut all = vec![];
+        if size == 0 {
+            return all;
+        }
+        for id in ids {
+            all.push(Expression::Var(Variable(id.to_string(), None)));
+        }
+        for body in expressions(size - 1, ids) {
+            for id in ids {
+                let id = Variable(id.to_string(), None);
+                all.push(Expression::Abs(Abstraction(id, Box::new(body.clone()))));
+            }
+        }
+        for n in 1..size - 1 {
+            for e1 in expressions(n, ids) {
+                for e2 in expressions(size - 1 - n, ids) {
+                    all.push(Expression::App(Application(
+                        Box::new(e1.clone()),
+                        Box::new(e2),
+                    )));
+                }
+            }
+        }
+        all
+    }
+
+    #[test]
+    fn round_trip() {
+        let parser = ExpressionParser::new();
+
+        let mut all = expressions(5, &["x", "y"]);
+        for source in &[
+            r"\\\",
+            r"\x.\\\",
+            r"(\x.) y",
+            r"λx:t.λy.x:t y",
+            r"λf:(a→b)→a→b x:a→b.f x",
+        ] {
+            all.push(parser.parse(source).unwrap());
+        }
+
+        // Substitution renames binders with primes, to avoid capture.
+        for source in &[
+            r"(λx.λy.x y) y",
+            r"(λx.λy.λy'.x y y') (y y')",
+            r"λy.(λx.λy.x) y",
+        ] {
+            let e = parser.parse(source).unwrap();
+            all.push(e.normalize(&Strategy::Normal(false)));
+        }
+
+        for expression in all {
+            for style in &STYLES {
+                let source = expression.pretty(*style).to_string();
+                assert_eq!(expression, parser.parse(&source).unwrap(), "{}", source);
+            }
+        }
+    }
+}
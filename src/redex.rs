@@ -0,0 +1,293 @@
+use crate::{Abstraction, Application, Expression, Spans};
+
+/// One move from an expression to one of its subexpressions
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+    /// Into the body of an abstraction
+    Abs,
+    /// Into the function of an application
+    Left,
+    /// Into the argument of an application
+    Right,
+}
+
+/// A reducible expression, with the path of moves to it from the root
+///
+/// ```
+/// use lalrpop_lambda::{Move, Redex};
+/// use lalrpop_lambda::parse::ExpressionParser;
+///
+/// let e = ExpressionParser::new().parse(r"λy.(λx.x) y").unwrap();
+/// assert_eq!(
+///     vec![Redex::Eta(vec![]), Redex::Beta(vec![Move::Abs])],
+///     e.redexes()
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Redex {
+    /// An application of an abstraction, `(λx.e1) e2`
+    Beta(Vec<Move>),
+    /// An abstraction `λx.(e x)`, where `x` isn't free in `e`
+    Eta(Vec<Move>),
+}
+
+impl Redex {
+    /// The moves from the root of the expression to this redex
+    pub fn path(&self) -> &[Move] {
+        match self {
+            Redex::Beta(path) | Redex::Eta(path) => path,
+        }
+    }
+}
+
+impl Expression {
+    /// Every β- and η-redex, in leftmost-outermost order
+    ///
+    /// The first redex is the one [`Strategy::Normal(true)`] contracts next,
+    /// see [`Expression::reduce_at`].
+    ///
+    /// [`Strategy::Normal(true)`]: crate::Strategy::Normal
+    pub fn redexes(&self) -> Vec<Redex> {
+        let mut redexes = vec![];
+        let mut path = vec![];
+        // Walk the tree without recursion, keeping the move made into each
+        // expression on the stack, and the length of the path before it.
+        let mut stack = vec![(self, None, 0)];
+        while let Some((e, step, depth)) = stack.pop() {
+            path.truncate(depth);
+            path.extend(step);
+            match e {
+                Expression::Var(_) => {}
+                Expression::Abs(Abstraction(_, body)) => {
+                    if e.is_η_redex() {
+                        redexes.push(Redex::Eta(path.clone()));
+                    }
+                    stack.push((body, Some(Move::Abs), path.len()));
+                }
+                Expression::App(Application(e1, e2)) => {
+                    if let Expression::Abs(_) = **e1 {
+                        redexes.push(Redex::Beta(path.clone()));
+                    }
+                    stack.push((e2, Some(Move::Right), path.len()));
+                    stack.push((e1, Some(Move::Left), path.len()));
+                }
+            }
+        }
+        redexes
+    }
+
+    /// The subexpression at the end of `path`, if every move can be made
+    ///
+    /// ```
+    /// use lalrpop_lambda::Move;
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let e = parser.parse(r"λx.f (x y)").unwrap();
+    /// let xy = parser.parse("x y").unwrap();
+    /// assert_eq!(Some(&xy), e.at(&[Move::Abs, Move::Right]));
+    /// assert_eq!(None, e.at(&[Move::Left]));
+    /// ```
+    pub fn at(&self, path: &[Move]) -> Option<&Expression> {
+        let mut e = self;
+        for step in path {
+            e = match (step, e) {
+                (Move::Abs, Expression::Abs(Abstraction(_, body))) => body,
+                (Move::Left, Expression::App(Application(e1, _))) => e1,
+                (Move::Right, Expression::App(Application(_, e2))) => e2,
+                _ => return None,
+            };
+        }
+        Some(e)
+    }
+
+    /// Contract the redex at the end of `path`, leaving the rest of the
+    /// expression as it is
+    ///
+    /// Returns `None` when the path doesn't lead to a β- or η-redex. This
+    /// lets a user choose each step of a reduction, for example from the
+    /// paths given by [`Expression::redexes`].
+    ///
+    /// ```
+    /// use lalrpop_lambda::Move;
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let e = parser.parse(r"(λx.x) ((λy.y) z)").unwrap();
+    /// let inner = parser.parse(r"(λx.x) z").unwrap();
+    /// assert_eq!(Some(inner), e.reduce_at(&[Move::Right]));
+    /// assert_eq!(None, e.reduce_at(&[Move::Left]));
+    /// ```
+    pub fn reduce_at(&self, path: &[Move]) -> Option<Expression> {
+        let (step, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return self.contract(),
+        };
+        match (step, self) {
+            (Move::Abs, Expression::Abs(Abstraction(id, body))) => {
+                let body = body.reduce_at(rest)?;
+                Some(Expression::Abs(Abstraction(id.clone(), Box::new(body))))
+            }
+            (Move::Left, Expression::App(Application(e1, e2))) => {
+                let e1 = e1.reduce_at(rest)?;
+                Some(Expression::App(Application(Box::new(e1), e2.clone())))
+            }
+            (Move::Right, Expression::App(Application(e1, e2))) => {
+                let e2 = e2.reduce_at(rest)?;
+                Some(Expression::App(Application(e1.clone(), Box::new(e2))))
+            }
+            _ => None,
+        }
+    }
+
+    // Contract this expression, when it's a redex itself.
+    fn contract(&self) -> Option<Expression> {
+        match self {
+            Expression::App(Application(e1, e2)) => match &**e1 {
+                Expression::Abs(Abstraction(id, body)) => Some(body.substitute(e2, id)),
+                _ => None,
+            },
+            Expression::Abs(Abstraction(_, body)) if self.is_η_redex() => match &**body {
+                Expression::App(Application(e1, _)) => Some((**e1).clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // λx.(e x) -> e whenever x does not appear free in e, as in
+    // `Expression::apply`.
+    fn is_η_redex(&self) -> bool {
+        match self {
+            Expression::Abs(Abstraction(id, body)) => match &**body {
+                Expression::App(Application(e1, e2)) => match &**e2 {
+                    Expression::Var(x) => id == x && !e1.free_variables().contains(id),
+                    _ => false,
+                },
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl Spans {
+    /// The spans of the subexpression at the end of `path`, see
+    /// [`Expression::at`]
+    ///
+    /// ```
+    /// use lalrpop_lambda::parse::SpannedExpressionParser;
+    ///
+    /// let source = r"a (λy.f y)";
+    /// let (e, spans) = SpannedExpressionParser::new().parse(source).unwrap();
+    /// let redex = &e.redexes()[0];
+    /// let span = spans.at(redex.path()).unwrap().span();
+    /// assert_eq!(r"λy.f y", &source[span.start..span.end]);
+    /// ```
+    pub fn at(&self, path: &[Move]) -> Option<&Spans> {
+        let mut spans = self;
+        for step in path {
+            spans = match (step, spans) {
+                (Move::Abs, Spans::Abs(_, body)) => body,
+                (Move::Left, Spans::App(_, e1, _)) => e1,
+                (Move::Right, Spans::App(_, _, e2)) => e2,
+                _ => return None,
+            };
+        }
+        Some(spans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{ExpressionParser, SpannedExpressionParser};
+    use crate::Strategy;
+
+    #[test]
+    fn redexes() {
+        let parser = ExpressionParser::new();
+        let redexes = |source| parser.parse(source).unwrap().redexes();
+
+        assert_eq!(Vec::<Redex>::new(), redexes(r"λx.x y"));
+        assert_eq!(vec![Redex::Beta(vec![])], redexes(r"(λx.x) y"));
+        assert_eq!(
+            vec![
+                Redex::Beta(vec![]),
+                Redex::Eta(vec![Move::Left]),
+                Redex::Beta(vec![Move::Left, Move::Abs]),
+                Redex::Eta(vec![Move::Right]),
+            ],
+            redexes(r"(λx.(λy.y) x) (λz.f z)")
+        );
+        // `x` is free in `x x`, so this isn't an η-redex.
+        assert_eq!(Vec::<Redex>::new(), redexes(r"λx.x x"));
+        assert_eq!(
+            vec![Redex::Eta(vec![]), Redex::Beta(vec![Move::Abs])],
+            redexes(r"λx.(λy.y) x")
+        );
+    }
+
+    #[test]
+    fn reduce_at() {
+        let parser = ExpressionParser::new();
+        let e = parser.parse(r"(λx.(λy.y) x) (λz.f z)").unwrap();
+        let reduce_at = |path: &[Move]| e.reduce_at(path).map(|e| e.to_string());
+
+        assert_eq!(Some(r"(λy.y) (λz.f z)".into()), reduce_at(&[]));
+        assert_eq!(
+            Some(r"(λx.x) (λz.f z)".into()),
+            reduce_at(&[Move::Left, Move::Abs])
+        );
+        assert_eq!(Some(r"(λx.(λy.y) x) f".into()), reduce_at(&[Move::Right]));
+        // `λx.(λy.y) x` is both an η-redex, and has a β-redex in its body.
+        assert_eq!(Some(r"(λy.y) (λz.f z)".into()), reduce_at(&[Move::Left]));
+        assert_eq!(None, reduce_at(&[Move::Right, Move::Abs]));
+        assert_eq!(None, reduce_at(&[Move::Left, Move::Abs, Move::Left]));
+        assert_eq!(None, reduce_at(&[Move::Abs]));
+
+        // Substitution still avoids capture.
+        let e = parser.parse(r"λy.(λx.λy.x) y").unwrap();
+        assert_eq!("λy y'.y", e.reduce_at(&[Move::Abs]).unwrap().to_string());
+    }
+
+    #[test]
+    fn leftmost_outermost() {
+        let parser = ExpressionParser::new();
+        for source in &[
+            r"(λx.x) ((λy.y) z)",
+            r"x ((λy.y) z) ((λy.y) w)",
+            r"λx.(λy.y) x",
+            r"λx.f x ((λy.y) x)",
+            r"(λf.λx.f (f x)) (λf.λx.f (f x))",
+            r"λf.(λx.f (x x)) (λx.f (x x))",
+        ] {
+            let e = parser.parse(source).unwrap();
+            let first = &e.redexes()[0];
+            assert_eq!(
+                e.apply(&Strategy::Normal(true)),
+                e.reduce_at(first.path()),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn spans() {
+        let source = r"a ((λx.x) b) (λy.f y)";
+        let (e, spans) = SpannedExpressionParser::new().parse(source).unwrap();
+        let text = |path: &[Move]| {
+            let span = spans.at(path).unwrap().span();
+            &source[span.start..span.end]
+        };
+
+        let redexes = e.redexes();
+        assert_eq!(2, redexes.len());
+        let beta = redexes[0].path().to_vec();
+        assert_eq!(r"λx.x", text(&[&beta[..], &[Move::Left]].concat()));
+        assert_eq!(r"b", text(&[&beta[..], &[Move::Right]].concat()));
+        assert_eq!(r"λy.f y", text(redexes[1].path()));
+        assert_eq!(None, spans.at(&[Move::Abs]));
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Abstraction, Application, Expression, Variable};
+
+impl Expression {
+    /// Replace the free variables named in `env` with their definitions
+    ///
+    /// Names bound by an abstraction shadow the environment, and binders are
+    /// α-renamed when they would capture a free variable of a definition.
+    /// Definitions are substituted as they are, so names free in them are
+    /// left alone, see [`Expression::resolve_recursive`].
+    ///
+    /// ```
+    /// # #![feature(box_syntax)]
+    /// # #[macro_use]
+    /// # extern crate lalrpop_lambda;
+    /// use std::collections::HashMap;
+    /// use std::convert::TryFrom;
+    ///
+    /// # fn main() {
+    /// let mut env = HashMap::new();
+    /// env.insert(variable!(id), abs!{x.x});
+    /// env.insert(variable!(ad), abs!{x.y});
+    /// env.insert(variable!(x), 1.into());
+    ///
+    /// assert_eq!(var!(q), var!(q).resolve(&env));
+    /// assert_eq!(Ok(1), u64::try_from(var!(x).resolve(&env)));
+    ///
+    /// // Works with functions too!
+    /// let id: fn(u64) -> u64 = var!(id).resolve(&env).into();
+    /// assert_eq!(1, id(1));
+    /// let ad: fn(u64) -> u64 = var!(ad).resolve(&env).into();
+    /// assert_eq!(0, ad(0));
+    /// assert_eq!(0, ad(1));
+    ///
+    /// // Bound names aren't replaced, and binders don't capture.
+    /// assert_eq!(abs!{x.x}, abs!{x.x}.resolve(&env));
+    /// assert_eq!("λy' x.y", abs!{y.ad}.resolve(&env).to_string());
+    /// # }
+    /// ```
+    pub fn resolve(&self, env: &HashMap<Variable, Expression>) -> Expression {
+        let free = self.free_variables();
+        let free: HashSet<&str> = free.iter().map(|v| v.0.as_str()).collect();
+        let env = env
+            .iter()
+            .filter(|(id, _)| free.contains(id.0.as_str()))
+            .map(|(id, e)| (id.0.as_str(), e))
+            .collect();
+        substitute(self, &env)
+    }
+
+    /// Replace the free variables named in `env` with their definitions, and
+    /// the names in those definitions, until none remain
+    ///
+    /// Definitions may refer to each other in any order. A definition which
+    /// refers back to itself, directly or through others, is closed with the
+    /// Y combinator, so recursive definitions only normalize under a lazy
+    /// strategy like [`Strategy::Normal`](crate::Strategy::Normal).
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use lalrpop_lambda::{Prelude, Strategy};
+    /// use lalrpop_lambda::parse::ExpressionParser;
+    ///
+    /// let mut prelude = Prelude::new();
+    /// prelude.define("
+    ///     even = λn.if (is_zero n) true (odd (pred n));
+    ///     odd = λn.if (is_zero n) false (even (pred n));
+    /// ").unwrap();
+    ///
+    /// let e = ExpressionParser::new().parse("even (succ (succ (succ zero)))").unwrap();
+    /// let e = e.resolve_recursive(&prelude).normalize(&Strategy::Normal(false));
+    /// assert_eq!(Ok(false), bool::try_from(e));
+    /// ```
+    pub fn resolve_recursive(&self, env: &HashMap<Variable, Expression>) -> Expression {
+        let mut definitions = Definitions {
+            env: env.iter().map(|(id, e)| (id.0.as_str(), e)).collect(),
+            stack: vec![],
+            resolved: HashMap::new(),
+        };
+        definitions.expression(self)
+    }
+}
+
+/// The state of a recursive resolution
+struct Definitions<'a> {
+    env: HashMap<&'a str, &'a Expression>,
+    /// The names whose definitions are being resolved, innermost last
+    stack: Vec<&'a str>,
+    /// Fully resolved definitions, which don't refer to any name on the stack
+    resolved: HashMap<&'a str, Expression>,
+}
+
+impl<'a> Definitions<'a> {
+    fn expression(&mut self, e: &Expression) -> Expression {
+        let mut definitions = HashMap::new();
+        for v in e.free_variables() {
+            if let Some((&name, _)) = self.env.get_key_value(v.0.as_str()) {
+                // Names on the stack are left free, to be bound by the
+                // fixpoint of their own definition.
+                if !self.stack.contains(&name) {
+                    definitions.insert(name, self.name(name));
+                }
+            }
+        }
+        substitute(e, &definitions.iter().map(|(&id, e)| (id, e)).collect())
+    }
+
+    fn name(&mut self, name: &'a str) -> Expression {
+        if let Some(e) = self.resolved.get(name) {
+            return e.clone();
+        }
+
+        let definition = self.env[name];
+        self.stack.push(name);
+        let mut e = self.expression(definition);
+        self.stack.pop();
+
+        let free = e.free_variables();
+        if free.iter().any(|v| v.0 == name) {
+            let id = Variable(name.into(), None);
+            e = app!({ y() }, { Expression::Abs(Abstraction(id, Box::new(e))) });
+        }
+        if !free.iter().any(|v| self.stack.contains(&v.0.as_str())) {
+            self.resolved.insert(name, e.clone());
+        }
+        e
+    }
+}
+
+/// Simultaneously replace the free variables named in `env`
+fn substitute(e: &Expression, env: &HashMap<&str, &Expression>) -> Expression {
+    if env.is_empty() {
+        return e.clone();
+    }
+    let free = env.values().flat_map(|e| e.free_variables()).collect();
+    substitute_free(e, env, &free)
+}
+
+/// Simultaneously replace the free variables named in `env`, given the free
+/// variables of its definitions
+fn substitute_free(
+    e: &Expression,
+    env: &HashMap<&str, &Expression>,
+    free: &HashSet<Variable>,
+) -> Expression {
+    match e {
+        Expression::Var(id) => env
+            .get(id.0.as_str())
+            .map_or_else(|| e.clone(), |&e| e.clone()),
+        Expression::Abs(Abstraction(id, body)) if env.contains_key(id.0.as_str()) => {
+            let mut env = env.clone();
+            env.remove(id.0.as_str());
+            let body = substitute_free(body, &env, free);
+            Expression::Abs(Abstraction(id.clone(), Box::new(body)))
+        }
+        Expression::Abs(Abstraction(id, body)) if free.iter().any(|v| v.0 == id.0) => {
+            let mut avoid: HashSet<_> = free.union(&body.variables()).cloned().collect();
+            avoid.extend(env.keys().map(|&id| Variable(id.into(), None)));
+            let fresh = id.fresh(&avoid);
+            let body = substitute_free(&body.replace(id, &fresh), env, free);
+            Expression::Abs(Abstraction(fresh, Box::new(body)))
+        }
+        Expression::Abs(Abstraction(id, body)) => {
+            let body = substitute_free(body, env, free);
+            Expression::Abs(Abstraction(id.clone(), Box::new(body)))
+        }
+        Expression::App(Application(e1, e2)) => Expression::App(Application(
+            Box::new(substitute_free(e1, env, free)),
+            Box::new(substitute_free(e2, env, free)),
+        )),
+    }
+}
+
+fn y() -> Expression {
+    let half = λ! {x.γ!(f,γ!(x,x))};
+    λ! {f.γ!({&half},{&half})}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::parse::ExpressionParser;
+    use crate::{Prelude, Strategy};
+
+    #[test]
+    fn resolve() {
+        let env = map! {
+            variable!(n) => 1.into(),
+        };
+
+        assert_eq!(var!(q), var!(q).resolve(&env));
+        assert_eq!(Ok(1), u64::try_from(var!(n).resolve(&env)));
+        assert_eq!(abs! {n.n}, abs! {n.n}.resolve(&env));
+        assert_eq!(Ok(1), u64::try_from(app!(abs! {a.a}, n).resolve(&env)));
+    }
+
+    #[test]
+    fn shadowing() {
+        let parser = ExpressionParser::new();
+        let env = map! {
+            variable!(a) => var!(b),
+        };
+
+        assert_eq!(
+            parser.parse("b (λa.a) (λc.b)").unwrap(),
+            parser.parse("a (λa.a) (λc.a)").unwrap().resolve(&env)
+        );
+        assert_eq!(
+            parser.parse("λa.λb.a (λa.a)").unwrap(),
+            parser.parse("λa.λb.a (λa.a)").unwrap().resolve(&env)
+        );
+    }
+
+    #[test]
+    fn capture() {
+        let parser = ExpressionParser::new();
+        let env = map! {
+            variable!(a) => parser.parse("x y").unwrap(),
+            variable!(b) => var!(x),
+        };
+
+        assert_eq!(
+            "λx' y'.x y (x' y') x'",
+            parser
+                .parse("λx.λy.a (x y) x")
+                .unwrap()
+                .resolve(&env)
+                .to_string()
+        );
+        assert_eq!(
+            "λx' c.x x' c",
+            parser
+                .parse("λx.λc.b x c")
+                .unwrap()
+                .resolve(&env)
+                .to_string()
+        );
+        // Only definitions which are used need to be avoided.
+        assert_eq!(abs! {y.y}, abs! {y.y}.resolve(&env));
+    }
+
+    #[test]
+    fn recursive() {
+        let parser = ExpressionParser::new();
+        let env = map! {
+            variable!(a) => var!(b),
+            variable!(b) => parser.parse("λx.c x").unwrap(),
+            variable!(c) => var!(x),
+        };
+
+        // Definitions are resolved in any order, without capture.
+        assert_eq!(
+            "λx'.(λx'.x x') x'",
+            parser
+                .parse("λx.a x")
+                .unwrap()
+                .resolve_recursive(&env)
+                .to_string()
+        );
+        assert_eq!(var!(x), var!(c).resolve_recursive(&env));
+    }
+
+    #[test]
+    fn cycles() {
+        let parser = ExpressionParser::new();
+        let env = map! {
+            variable!(bottom) => var!(bottom),
+            variable!(f) => parser.parse("λx.g x").unwrap(),
+            variable!(g) => parser.parse("λx.f x").unwrap(),
+        };
+
+        assert_eq!(
+            app!({ y() }, abs! {bottom.bottom}),
+            var!(bottom).resolve_recursive(&env)
+        );
+        let f = var!(f).resolve_recursive(&env);
+        assert!(f.free_variables().is_empty());
+        assert_eq!(
+            app!({ y() }, { parser.parse("λf.λx.(λx.f x) x").unwrap() }),
+            f
+        );
+    }
+
+    #[test]
+    fn recursion() {
+        let mut prelude = Prelude::new();
+        prelude
+            .define("triangle = λn.if (is_zero n) zero (add n (triangle (pred n)));")
+            .unwrap();
+        let e = ExpressionParser::new()
+            .parse("triangle (succ (succ (succ zero)))")
+            .unwrap();
+
+        // The definition of `triangle` is only substituted once by `resolve`.
+        let once = e.resolve(&prelude);
+        assert!(once.free_variables().contains(&variable!(triangle)));
+        let e = e
+            .resolve_recursive(&prelude)
+            .normalize(&Strategy::Normal(false));
+        assert_eq!(Ok(6), u64::try_from(e));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{Abstraction, Application, Expression, Variable};
+
+/// A byte range of parsed source
+///
+/// ```
+/// let parser = lalrpop_lambda::parse::SpannedExpressionParser::new();
+///
+/// let source = "λx.x y";
+/// let (_, spans) = parser.parse(source).unwrap();
+/// let span = spans.span();
+/// assert_eq!(source, &source[span.start..span.end]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The [`Span`] of each node of a parsed [`Expression`], in the same shape
+///
+/// Sugar like `let` and curried abstractions produces nodes which share the
+/// span of the source they came from.
+///
+/// ```
+/// use lalrpop_lambda::Spans;
+///
+/// let parser = lalrpop_lambda::parse::SpannedExpressionParser::new();
+///
+/// let source = "(λx.x) y";
+/// let (_, spans) = parser.parse(source).unwrap();
+/// if let Spans::App(_, function, argument) = spans {
+///     assert_eq!("λx.x", &source[function.span().start..function.span().end]);
+///     assert_eq!("y", &source[argument.span().start..argument.span().end]);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Spans {
+    Var(Span),
+    Abs(Span, Box<Spans>),
+    App(Span, Box<Spans>, Box<Spans>),
+}
+
+impl Spans {
+    /// The span of the whole expression
+    pub fn span(&self) -> Span {
+        match *self {
+            Spans::Var(span) | Spans::Abs(span, _) | Spans::App(span, _, _) => span,
+        }
+    }
+}
+
+/// An expression paired with its spans, built by the grammar
+pub(crate) type Spanned = (Expression, Spans);
+
+/// See [`Expression::build_abs`]
+///
+/// The outermost abstraction spans all of `span`, curried abstractions start
+/// at their variable.
+pub(crate) fn build_abs(
+    lambs: usize,
+    ids: Vec<(Variable, Span)>,
+    body: Option<Spanned>,
+    span: Span,
+) -> Spanned {
+    let (ids, id_spans): (Vec<_>, Vec<_>) = ids.into_iter().unzip();
+    let (body, mut spans) = match body {
+        Some((e, spans)) => (Some(e), spans),
+        None => (
+            None,
+            Spans::Var(Span {
+                start: span.end,
+                ..span
+            }),
+        ),
+    };
+
+    let id_count = id_spans.len();
+    for (i, id_span) in id_spans.into_iter().enumerate().rev() {
+        let start = if i == 0 && lambs == 1 {
+            span.start
+        } else {
+            id_span.start
+        };
+        spans = Spans::Abs(Span { start, ..span }, Box::new(spans));
+    }
+    for l in 0..lambs {
+        if l == 0 && id_count > 0 {
+            continue;
+        }
+        spans = Spans::Abs(span, Box::new(spans));
+    }
+
+    (Expression::build_abs(lambs, ids, body), spans)
+}
+
+pub(crate) fn app(e1: Spanned, e2: Spanned) -> Spanned {
+    let span = Span {
+        start: e1.1.span().start,
+        end: e2.1.span().end,
+    };
+    (
+        Expression::App(Application(Box::new(e1.0), Box::new(e2.0))),
+        Spans::App(span, Box::new(e1.1), Box::new(e2.1)),
+    )
+}
+
+/// `(λid.body) e`, spanning all of `span`
+pub(crate) fn bind(id: Variable, e: Spanned, body: Spanned, span: Span) -> Spanned {
+    (
+        Expression::App(Application(
+            Box::new(Expression::Abs(Abstraction(id, Box::new(body.0)))),
+            Box::new(e.0),
+        )),
+        Spans::App(
+            span,
+            Box::new(Spans::Abs(span, Box::new(body.1))),
+            Box::new(e.1),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{ExpressionParser, SpannedExpressionParser, SpannedProgramParser};
+    use pretty_assertions::assert_eq;
+
+    fn span(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    #[test]
+    fn expression() {
+        let parser = SpannedExpressionParser::new();
+
+        assert_eq!(Spans::Var(span(0, 1)), parser.parse("x").unwrap().1);
+        assert_eq!(Spans::Var(span(1, 2)), parser.parse("(x)").unwrap().1);
+        assert_eq!(
+            Spans::App(
+                span(0, 5),
+                Box::new(Spans::App(
+                    span(0, 3),
+                    Box::new(Spans::Var(span(0, 1))),
+                    Box::new(Spans::Var(span(2, 3)))
+                )),
+                Box::new(Spans::Var(span(4, 5)))
+            ),
+            parser.parse("a b c").unwrap().1
+        );
+        assert_eq!(
+            Spans::Abs(
+                span(0, 6),
+                Box::new(Spans::Abs(span(3, 6), Box::new(Spans::Var(span(5, 6)))))
+            ),
+            parser.parse(r"\x y.x").unwrap().1
+        );
+        assert_eq!(
+            Spans::App(
+                span(0, 14),
+                Box::new(Spans::Abs(span(0, 14), Box::new(Spans::Var(span(13, 14))))),
+                Box::new(Spans::Var(span(8, 9)))
+            ),
+            parser.parse(r"let x = y in x").unwrap().1
+        );
+    }
+
+    #[test]
+    fn same_expression() {
+        for source in &[
+            r"x",
+            r"\x.x",
+            r"\\\x y.x",
+            r"\\\",
+            r"(\x.x x) (\y.y)",
+            r"let x = \y.y in x x",
+        ] {
+            assert_eq!(
+                ExpressionParser::new().parse(source).unwrap(),
+                SpannedExpressionParser::new().parse(source).unwrap().0
+            );
+        }
+    }
+
+    #[test]
+    fn program() {
+        let source = "id = \\x.x;\nid id";
+        let (_, spans) = SpannedProgramParser::new().parse(source).unwrap();
+        assert_eq!(span(0, 16), spans.span());
+        if let Spans::App(_, _, definition) = spans {
+            assert_eq!(
+                r"\x.x",
+                &source[definition.span().start..definition.span().end]
+            );
+        } else {
+            panic!("expected an application");
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::{Abstraction, Application, Expression, Style, Variable};
+
+/// A simple type, written in the annotation of a variable
+///
+/// Arrows associate to the right, so `a → b → c` is `a → (b → c)`. Both `→`
+/// and `->` are accepted.
+///
+/// ```
+/// use lalrpop_lambda::Type;
+/// use lalrpop_lambda::parse::TypeParser;
+///
+/// let parser = TypeParser::new();
+/// let a = || Box::new(Type::Base("a".into()));
+///
+/// assert_eq!(
+///     Type::Arrow(Box::new(Type::Arrow(a(), a())), Box::new(Type::Arrow(a(), a()))),
+///     parser.parse("(a → a)->a → a").unwrap());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    Base(String),
+    Arrow(Box<Type>, Box<Type>),
+}
+
+/// The types of the free variables of an expression
+pub type Context = HashMap<String, Type>;
+
+/// A reason an expression isn't well typed, with the subterm at fault
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeError {
+    /// A variable with neither a type in the context, nor an annotation
+    Unbound(Variable),
+    /// An abstraction whose bound variable has no annotation
+    Unannotated(Expression),
+    /// An expression in function position whose type isn't an arrow
+    NotAFunction { expression: Expression, found: Type },
+    /// An expression whose type differs from the one required of it
+    Mismatch {
+        expression: Expression,
+        expected: Type,
+        found: Type,
+    },
+    /// An expression which could only be typed if `variable` were `ty`,
+    /// which contains it
+    Occurs {
+        expression: Expression,
+        variable: Type,
+        ty: Type,
+    },
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeError::Unbound(id) => write!(f, "{} has no type", id),
+            TypeError::Unannotated(e) => {
+                write!(f, "{} is missing a type annotation on its argument", e)
+            }
+            TypeError::NotAFunction { expression, found } => {
+                write!(
+                    f,
+                    "{} has type {}, which can't be applied",
+                    expression, found
+                )
+            }
+            TypeError::Mismatch {
+                expression,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has type {}, but {} was expected",
+                expression, found, expected
+            ),
+            TypeError::Occurs {
+                expression,
+                variable,
+                ty,
+            } => write!(
+                f,
+                "{} needs the infinite type {} = {}",
+                expression, variable, ty
+            ),
+        }
+    }
+}
+
+impl Error for TypeError {}
+
+impl Expression {
+    /// The type of this expression in the simply typed λ-calculus
+    ///
+    /// Every abstraction must annotate its bound variable. Free variables
+    /// have their type given by the context, or by their own annotation.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lalrpop_lambda::{Context, Type, TypeError};
+    /// use lalrpop_lambda::parse::{ExpressionParser, TypeParser};
+    ///
+    /// let parser = ExpressionParser::new();
+    /// let types = TypeParser::new();
+    ///
+    /// let compose = parser.parse(r"\f:b→c g:a→b x:a.f (g x)").unwrap();
+    /// assert_eq!(
+    ///     Ok(types.parse("(b → c) → (a → b) → a → c").unwrap()),
+    ///     compose.typecheck(&Context::new()));
+    ///
+    /// let mut context = HashMap::new();
+    /// context.insert("x".into(), Type::Base("a".into()));
+    /// let apply = parser.parse(r"\f:a→a.f (f x)").unwrap();
+    /// assert_eq!(
+    ///     Ok(types.parse("(a → a) → a").unwrap()),
+    ///     apply.typecheck(&context));
+    ///
+    /// let error = parser.parse(r"\x:a.x x").unwrap().typecheck(&context);
+    /// assert_eq!("x has type a, which can't be applied", error.unwrap_err().to_string());
+    /// ```
+    pub fn typecheck(&self, context: &Context) -> Result<Type, TypeError> {
+        match self {
+            Expression::Var(id) => match (context.get(&id.0), &id.1) {
+                (Some(ty), Some(annotation)) if ty != annotation => Err(TypeError::Mismatch {
+                    expression: self.clone(),
+                    expected: ty.clone(),
+                    found: annotation.clone(),
+                }),
+                (Some(ty), _) | (None, Some(ty)) => Ok(ty.clone()),
+                (None, None) => Err(TypeError::Unbound(id.clone())),
+            },
+            Expression::Abs(Abstraction(id, body)) => {
+                let ty = match &id.1 {
+                    Some(ty) => ty.clone(),
+                    None => return Err(TypeError::Unannotated(self.clone())),
+                };
+                let mut context = context.clone();
+                context.insert(id.0.clone(), ty.clone());
+                Ok(Type::Arrow(
+                    Box::new(ty),
+                    Box::new(body.typecheck(&context)?),
+                ))
+            }
+            Expression::App(Application(e1, e2)) => match e1.typecheck(context)? {
+                Type::Arrow(domain, range) => {
+                    let ty = e2.typecheck(context)?;
+                    if ty == *domain {
+                        Ok(*range)
+                    } else {
+                        Err(TypeError::Mismatch {
+                            expression: *e2.clone(),
+                            expected: *domain,
+                            found: ty,
+                        })
+                    }
+                }
+                found => Err(TypeError::NotAFunction {
+                    expression: *e1.clone(),
+                    found,
+                }),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.pretty(Style::default()))
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{ExpressionParser, TypeParser};
+    use pretty_assertions::assert_eq;
+
+    fn ty(source: &str) -> Type {
+        TypeParser::new().parse(source).unwrap()
+    }
+
+    fn typecheck(source: &str) -> Result<Type, TypeError> {
+        let expression = ExpressionParser::new().parse(source).unwrap();
+        expression.typecheck(&Context::new())
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Type::Base("a".into()), ty("a"));
+        assert_eq!(ty("a → (b → c)"), ty("a → b → c"));
+        assert_ne!(ty("(a → b) → c"), ty("a → b → c"));
+        assert_eq!(ty("a → b"), ty("a -> b"));
+        assert_eq!(ty("a-b → c"), ty("a-b->c"));
+
+        let parser = ExpressionParser::new();
+        assert_eq!(
+            parser.parse(r"λx:a → a.x").unwrap(),
+            parser.parse(r"λx:a->a.x").unwrap()
+        );
+        assert_eq!(ty("a → b"), ty("(a)→(b)"));
+    }
+
+    #[test]
+    fn display() {
+        for source in &["a", "a → b → c", "(a → b) → c", "((a → b) → c) → d"] {
+            assert_eq!(*source, ty(source).to_string());
+        }
+
+        let id = ExpressionParser::new().parse(r"\f:a→b.f").unwrap();
+        assert_eq!("λf:(a → b).f", id.to_string());
+        assert_eq!(id, ExpressionParser::new().parse(&id.to_string()).unwrap());
+    }
+
+    #[test]
+    fn well_typed() {
+        assert_eq!(Ok(ty("a → a")), typecheck(r"\x:a.x"));
+        assert_eq!(Ok(ty("a → b → a")), typecheck(r"\x:a y:b.x"));
+        assert_eq!(Ok(ty("b")), typecheck(r"(\x:a.y:b) z:a"));
+        assert_eq!(Ok(ty("b → a → a")), typecheck(r"\x:b.\x:a.x"));
+        assert_eq!(
+            Ok(ty("(a → a) → a → a")),
+            typecheck(r"\f:(a → a) x:a.f (f x)")
+        );
+    }
+
+    #[test]
+    fn context() {
+        let mut context = Context::new();
+        context.insert("f".into(), ty("a → b"));
+        context.insert("x".into(), ty("a"));
+
+        let parser = ExpressionParser::new();
+        let typecheck = |source| parser.parse(source).unwrap().typecheck(&context);
+        assert_eq!(Ok(ty("b")), typecheck("f x"));
+        assert_eq!(Ok(ty("b")), typecheck("f x:a"));
+        assert_eq!(Ok(ty("b → b")), typecheck(r"\x:b.x"));
+        assert_eq!(
+            Err(TypeError::Mismatch {
+                expression: Expression::Var(variable!(x, b)),
+                expected: ty("a"),
+                found: ty("b"),
+            }),
+            typecheck("f x:b")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(TypeError::Unbound(variable!(y))), typecheck(r"\x:a.y"));
+        assert_eq!(
+            Err(TypeError::Unannotated(λ! {y.y})),
+            typecheck(r"\x:a.(\y.y) x")
+        );
+        assert_eq!(
+            Err(TypeError::NotAFunction {
+                expression: var!(x),
+                found: ty("a"),
+            }),
+            typecheck(r"\x:a.x x")
+        );
+        assert_eq!(
+            Err(TypeError::Mismatch {
+                expression: var!(y),
+                expected: ty("a → a"),
+                found: ty("b"),
+            }),
+            typecheck(r"\f:(a → a) → a y:b.f y")
+        );
+    }
+
+    #[test]
+    fn typed_macros() {
+        assert_eq!(Ok(ty("t → t")), λ! {x:t.x}.typecheck(&Context::new()));
+        assert_eq!(
+            Ok(ty("t → u → t")),
+            abs! {x:t y:u.x}.typecheck(&Context::new())
+        );
+    }
+}
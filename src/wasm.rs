@@ -19,8 +19,9 @@
 //! ```
 //!
 //! See `examples/site` for more.
+use std::convert::TryFrom;
 use wasm_bindgen::prelude::*;
-use crate::{parse, Expression};
+use crate::{parse, Diagnostic, Expression, Limits, Move, Prelude};
 use crate::normal::Strategy;
 
 /// A parsed λ-expression
@@ -40,13 +41,16 @@ impl Exp {
     /// new lambda.Exp(false);
     /// new lambda.Exp("*wtf");  // Throws exception.
     /// ```
+    ///
+    /// Parse errors are thrown as a [`Diagnostic`], rendered with the line of
+    /// input containing the error underlined.
     #[wasm_bindgen(constructor)]
     pub fn new(v: JsValue) -> Result<Exp, JsValue> {
         if let Some(s) = v.as_string() {
             let parser = parse::ExpressionParser::new();
             match parser.parse(&s) {
                 Ok(e) => Ok(Exp(e)),
-                Err(e) => Err(JsValue::from_str(&format!("{}", e))),
+                Err(e) => Err(JsValue::from_str(&Diagnostic::new(&e, &s).render(&s))),
             }
         } else if let Some(n) = v.as_f64() {
             Ok(Exp(Expression::from(n as u64)))
@@ -57,6 +61,18 @@ impl Exp {
         }
     }
 
+    /// Parse a program which may use the names of the [`Prelude`]
+    ///
+    /// ```js
+    /// lambda.Exp.withPrelude("two = succ (succ zero); mul two two");
+    /// ```
+    #[wasm_bindgen(js_name = withPrelude)]
+    pub fn with_prelude(s: &str) -> Result<Exp, JsValue> {
+        match Prelude::new().parse(s) {
+            Ok(e) => Ok(Exp(e)),
+            Err(e) => Err(JsValue::from_str(&Diagnostic::new(&e, s).render(s))),
+        }
+    }
 
     pub fn applicative(&self, η: bool) -> Self {
         Exp(self.0.normalize(&Strategy::Applicative(η)))
@@ -78,6 +94,90 @@ impl Exp {
         Exp(self.0.normalize(&Strategy::HeadSpine(η)))
     }
 
+    pub fn hybrid_applicative(&self) -> Self {
+        Exp(self.0.normalize(&Strategy::HybridApplicative))
+    }
+
+    pub fn hybrid_normal(&self) -> Self {
+        Exp(self.0.normalize(&Strategy::HybridNormal))
+    }
+
+    pub fn call_by_need(&self) -> Self {
+        Exp(self.0.normalize(&Strategy::CallByNeed))
+    }
+
+    /// Normalize with the strategy of the method with the same name, and
+    /// report the work it took, see [`crate::Stats`]
+    ///
+    /// Gives up after `fuel` steps, throwing a message with the term reached,
+    /// like `exceeded 1000 reduction steps, reached (λx.x x) (λx.x x)`.
+    ///
+    /// ```js
+    /// let e = new lambda.Exp("(\\x.x x) ((\\y.y) z)");
+    /// e.stats("normal", false, 1000).betaSteps;  // 3
+    /// e.stats("call_by_value", false, 1000).betaSteps;  // 2
+    /// ```
+    pub fn stats(&self, strategy: &str, η: bool, fuel: usize) -> Result<Stats, JsValue> {
+        let strategy = match strategy {
+            "applicative" => Strategy::Applicative(η),
+            "call_by_value" => Strategy::CallByValue,
+            "normal" => Strategy::Normal(η),
+            "call_by_name" => Strategy::CallByName,
+            "head_spine" => Strategy::HeadSpine(η),
+            "hybrid_applicative" => Strategy::HybridApplicative,
+            "hybrid_normal" => Strategy::HybridNormal,
+            "call_by_need" => Strategy::CallByNeed,
+            _ => return Err(JsValue::from_str(&format!("unknown strategy {}", strategy))),
+        };
+        let limits = Limits {
+            steps: Some(fuel),
+            ..Limits::default()
+        };
+        let mut stats = crate::Stats::default();
+        match self.0.normalize_with_stats(&strategy, &limits, &mut stats) {
+            Ok(_) => Ok(Stats(stats)),
+            Err(e) => Err(JsValue::from_str(&format!(
+                "{}, reached {}",
+                e, e.expression
+            ))),
+        }
+    }
+
+    /// Every β- and η-redex, in leftmost-outermost order, see
+    /// [`Expression::redexes`]
+    ///
+    /// ```js
+    /// let e = new lambda.Exp("(\\x.x) ((\\y.y) z)");
+    /// e.redexes().map(r => r.path);  // ["", "r"]
+    /// ```
+    pub fn redexes(&self) -> Vec<Redex> {
+        self.0.redexes().into_iter().map(Redex).collect()
+    }
+
+    /// Contract the redex at the end of a path, written as for
+    /// [`Redex::path`], see [`Expression::reduce_at`]
+    ///
+    /// ```js
+    /// let e = new lambda.Exp("(\\x.x) ((\\y.y) z)");
+    /// e.reduceAt("r").toString();  // "(λx.x) z"
+    /// e.reduceAt("l");  // Throws exception.
+    /// ```
+    #[wasm_bindgen(js_name = reduceAt)]
+    pub fn reduce_at(&self, path: &str) -> Result<Exp, JsValue> {
+        let path = path
+            .chars()
+            .map(|c| match c {
+                'a' => Ok(Move::Abs),
+                'l' => Ok(Move::Left),
+                'r' => Ok(Move::Right),
+                _ => Err(JsValue::from_str(&format!("unknown move {}", c))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.0
+            .reduce_at(&path)
+            .map(Exp)
+            .ok_or_else(|| JsValue::from_str("no redex at this path"))
+    }
 
     /// See [`std::fmt::Display`]
     ///
@@ -90,26 +190,96 @@ impl Exp {
         format!("{}", self.0)
     }
 
-    /// See `From<Expression> for u64`
+    /// See `TryFrom<Expression> for u64`
     ///
     /// ```js
     /// let two = new lambda.Exp("\\f.\\x.(f (f x))");
     /// console.log(`${two.toNumber()}`);
+    /// new lambda.Exp("\\x.x").toNumber();  // Throws exception.
     /// ```
     #[wasm_bindgen(method, js_name = toNumber)]
-    pub fn to_number(&self) -> usize {
-        u64::from(self.0.clone()) as usize
+    pub fn to_number(&self) -> Result<usize, JsValue> {
+        u64::try_from(self.0.clone())
+            .map(|n| n as usize)
+            .map_err(|e| JsValue::from_str(&format!("{}", e)))
     }
 
-    /// See `From<Expression> for bool`
+    /// See `TryFrom<Expression> for bool`
     ///
     ///
     /// ```js
     /// let t = new lambda.Exp("\\a.\\b.a");
     /// console.log(`${t.toBool()}`);
+    /// new lambda.Exp("\\x.x").toBool();  // Throws exception.
     /// ```
     #[wasm_bindgen(method, js_name = toBool)]
-    pub fn to_bool(&self) -> bool {
-        self.0.clone().into()
+    pub fn to_bool(&self) -> Result<bool, JsValue> {
+        bool::try_from(self.0.clone()).map_err(|e| JsValue::from_str(&format!("{}", e)))
+    }
+}
+
+/// The work done normalizing an [`Exp`], see [`Exp::stats`]
+#[wasm_bindgen]
+pub struct Stats(crate::Stats);
+
+#[wasm_bindgen]
+impl Stats {
+    #[wasm_bindgen(getter, js_name = betaSteps)]
+    pub fn beta_steps(&self) -> usize {
+        self.0.beta_steps
+    }
+
+    #[wasm_bindgen(getter, js_name = etaSteps)]
+    pub fn eta_steps(&self) -> usize {
+        self.0.eta_steps
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn substitutions(&self) -> usize {
+        self.0.substitutions
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn renames(&self) -> usize {
+        self.0.renames
+    }
+
+    #[wasm_bindgen(getter, js_name = peakSize)]
+    pub fn peak_size(&self) -> usize {
+        self.0.peak_size
+    }
+
+    #[wasm_bindgen(getter, js_name = maxDepth)]
+    pub fn max_depth(&self) -> usize {
+        self.0.max_depth
+    }
+}
+
+/// A redex of an [`Exp`], see [`Exp::redexes`]
+#[wasm_bindgen]
+pub struct Redex(crate::Redex);
+
+#[wasm_bindgen]
+impl Redex {
+    /// The moves from the root to this redex, one letter each: `a` into the
+    /// body of an abstraction, `l` into the function of an application, and
+    /// `r` into its argument
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> String {
+        self.0
+            .path()
+            .iter()
+            .map(|step| match step {
+                Move::Abs => 'a',
+                Move::Left => 'l',
+                Move::Right => 'r',
+            })
+            .collect()
+    }
+
+    /// Is this an η-redex, rather than a β-redex?
+    #[wasm_bindgen(getter)]
+    pub fn eta(&self) -> bool {
+        matches!(self.0, crate::Redex::Eta(_))
     }
 }